@@ -0,0 +1,216 @@
+use reqwest::RequestBuilder as HTTPRequestBuilder;
+use async_trait::async_trait;
+use serde_json::Value;
+
+use super::util::{
+    extract_string_with_default,
+    extract_string_with_error,
+    send_for_json,
+    Request,
+    Error
+};
+use crate::REDDIT_API_URL;
+use super::util::RedditClient;
+
+#[derive(Clone, Default, Debug)]
+pub struct Account {
+    pub id: String,
+    pub name: String,
+    pub link_karma: i64,
+    pub comment_karma: i64,
+    pub created_utc: f64,
+    pub is_mod: bool,
+    // Only known for the logged in account.
+    pub has_mail: bool,
+    // Number of unread inbox items, only known for the logged in account.
+    pub inbox_count: u64
+}
+
+impl Account {
+    // Parses the `data` object of a `t2` thing.
+    pub(crate) fn from_data(data: &Value) -> Result<Self, Error> {
+        Ok(Account {
+            id: extract_string_with_error!(data["id"], "data.id"),
+            name: extract_string_with_error!(data["name"], "data.name"),
+            link_karma: data["link_karma"].as_i64().unwrap_or_default(),
+            comment_karma: data["comment_karma"].as_i64().unwrap_or_default(),
+            created_utc: data["created_utc"].as_f64().unwrap_or_default(),
+            is_mod: data["is_mod"].as_bool().unwrap_or_default(),
+            has_mail: data["has_mail"].as_bool().unwrap_or_default(),
+            inbox_count: data["inbox_count"].as_u64().unwrap_or_default()
+        })
+    }
+
+    pub fn fullname(&self) -> String {
+        format!("t2_{}", self.id)
+    }
+}
+
+// The account the client is logged in as. RedditClient::identity caches the result.
+#[derive(Clone)]
+pub struct MeRequest;
+
+#[async_trait]
+impl Request<Account> for MeRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        Ok(client.http_client
+            .get(format!("{}api/v1/me", REDDIT_API_URL))
+            .bearer_auth(client.access_token()?))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<Account, Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        Account::from_data(&v)
+    }
+}
+
+// Karma the logged in user earned in a single subreddit.
+#[derive(Clone, Default, Debug)]
+pub struct SubredditKarma {
+    pub subreddit: String,
+    pub link_karma: i64,
+    pub comment_karma: i64
+}
+
+#[derive(Clone)]
+pub struct KarmaRequest;
+
+#[async_trait]
+impl Request<Vec<SubredditKarma>> for KarmaRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        Ok(client.http_client
+            .get(format!("{}api/v1/me/karma", REDDIT_API_URL))
+            .bearer_auth(client.access_token()?))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<Vec<SubredditKarma>, Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        parse_karma_list(&v)
+    }
+}
+
+fn parse_karma_list(v: &Value) -> Result<Vec<SubredditKarma>, Error> {
+    v["data"].as_array()
+        .ok_or(Error::InternalError("Can't convert to vector".to_string()))?
+        .iter()
+        .map(|karma| -> Result<SubredditKarma, Error> {
+            Ok(SubredditKarma {
+                subreddit: extract_string_with_error!(karma["sr"], "data.sr"),
+                link_karma: karma["link_karma"].as_i64().unwrap_or_default(),
+                comment_karma: karma["comment_karma"].as_i64().unwrap_or_default()
+            })
+        })
+        .collect()
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct Trophy {
+    pub id: String,
+    pub award_id: String,
+    pub name: String,
+    pub description: String,
+    pub icon_70: String,
+    pub icon_40: String,
+    pub url: String,
+    // Seconds since the Unix epoch, if Reddit recorded when it was granted.
+    pub granted_at: Option<u64>
+}
+
+impl Trophy {
+    // Parses the `data` object of a `t6` thing.
+    pub(crate) fn from_data(data: &Value) -> Result<Self, Error> {
+        Ok(Trophy {
+            id: extract_string_with_default!(data["id"], "data.id"),
+            award_id: extract_string_with_default!(data["award_id"], "data.award_id"),
+            name: extract_string_with_error!(data["name"], "data.name"),
+            description: extract_string_with_default!(data["description"], "data.description"),
+            icon_70: extract_string_with_default!(data["icon_70"], "data.icon_70"),
+            icon_40: extract_string_with_default!(data["icon_40"], "data.icon_40"),
+            url: extract_string_with_default!(data["url"], "data.url"),
+            granted_at: data["granted_at"].as_u64()
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct TrophiesRequest {
+    username: String
+}
+
+impl TrophiesRequest {
+    pub fn new(username: &str) -> Self {
+        TrophiesRequest { username: username.to_string() }
+    }
+}
+
+#[async_trait]
+impl Request<Vec<Trophy>> for TrophiesRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        Ok(client.http_client
+            .get(format!("{}api/v1/user/{}/trophies", REDDIT_API_URL, self.username))
+            .bearer_auth(client.access_token()?))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<Vec<Trophy>, Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        parse_trophy_list(&v)
+    }
+}
+
+fn parse_trophy_list(v: &Value) -> Result<Vec<Trophy>, Error> {
+    v["data"]["trophies"].as_array()
+        .ok_or(Error::InternalError("Can't convert to vector".to_string()))?
+        .iter()
+        .map(|trophy| -> Result<Trophy, Error> { Trophy::from_data(&trophy["data"]) })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use serde_json::json;
+    use super::*;
+    use crate::api::util::test_client;
+
+    #[test]
+    fn test_me_request() -> Result<()> {
+        let req = MeRequest.construct(&test_client())?;
+        assert_eq!(req.url().path(), "/api/v1/me");
+
+        let v = json!({
+            "id": "abc", "name": "spez", "link_karma": 10, "comment_karma": -3,
+            "created_utc": 1118030400.0, "is_mod": true, "has_mail": true, "inbox_count": 2
+        });
+        let account = Account::from_data(&v)?;
+        assert_eq!(account.fullname(), "t2_abc");
+        assert_eq!(account.comment_karma, -3);
+        assert!(account.is_mod && account.has_mail);
+        assert_eq!(account.inbox_count, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_karma_and_trophies() -> Result<()> {
+        let client = test_client();
+        assert_eq!(KarmaRequest.construct(&client)?.url().path(), "/api/v1/me/karma");
+        assert_eq!(TrophiesRequest::new("spez").construct(&client)?.url().path(), "/api/v1/user/spez/trophies");
+
+        let v = json!({ "kind": "KarmaList", "data": [
+            { "sr": "rust", "comment_karma": 120, "link_karma": 30 },
+            { "sr": "programming", "comment_karma": -4, "link_karma": 0 }
+        ] });
+        let karma = parse_karma_list(&v)?;
+        assert_eq!(karma[0].subreddit, "rust");
+        assert_eq!((karma[1].link_karma, karma[1].comment_karma), (0, -4));
+
+        let v = json!({ "kind": "TrophyList", "data": { "trophies": [
+            { "kind": "t6", "data": { "name": "Verified Email", "icon_70": "https://i/70.png", "granted_at": null } },
+            { "kind": "t6", "data": { "name": "Five-Year Club", "award_id": "a", "granted_at": 1500000000 } }
+        ] } });
+        let trophies = parse_trophy_list(&v)?;
+        assert_eq!(trophies[0].icon_70, "https://i/70.png");
+        assert_eq!(trophies[0].granted_at, None);
+        assert_eq!(trophies[1].granted_at, Some(1500000000));
+        Ok(())
+    }
+}
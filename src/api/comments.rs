@@ -0,0 +1,693 @@
+use reqwest::RequestBuilder as HTTPRequestBuilder;
+use async_trait::async_trait;
+use serde_json::Value;
+
+use super::util::{
+    check_api_errors,
+    extract_string_with_default,
+    extract_string_with_error,
+    send_for_json,
+    Request,
+    RequestBuilder,
+    Error
+};
+use super::listing::Post;
+use crate::REDDIT_API_URL;
+use super::util::RedditClient;
+
+#[derive(Clone, Debug)]
+pub enum CommentSort {
+    Confidence,
+    Top,
+    New,
+    Controversial,
+    Old,
+    Random,
+    Qa,
+    Live
+}
+
+impl CommentSort {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            CommentSort::Confidence => "confidence",
+            CommentSort::Top => "top",
+            CommentSort::New => "new",
+            CommentSort::Controversial => "controversial",
+            CommentSort::Old => "old",
+            CommentSort::Random => "random",
+            CommentSort::Qa => "qa",
+            CommentSort::Live => "live"
+        }
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct Comment {
+    pub id: String,
+    pub name: String,
+    pub author: String,
+    pub body: String,
+    pub body_html: String,
+    pub subreddit: String,
+    pub link_id: String,
+    pub parent_id: String,
+    pub permalink: String,
+    pub score: i64,
+    pub depth: u32,
+    pub created_utc: f64,
+    // The user's vote: true for up, false for down and None if they have not voted.
+    pub likes: Option<bool>,
+    pub replies: Vec<CommentNode>
+}
+
+// Placeholder for comments Reddit left out of the response. A stub with no children is a
+// "continue this thread" link and can only be loaded by focusing on its parent comment.
+#[derive(Clone, Default, Debug)]
+pub struct MoreComments {
+    pub id: String,
+    pub name: String,
+    pub parent_id: String,
+    pub count: u64,
+    pub depth: u32,
+    pub children: Vec<String>
+}
+
+#[derive(Clone, Debug)]
+pub enum CommentNode {
+    Comment(Comment),
+    More(MoreComments)
+}
+
+#[derive(Clone, Debug)]
+pub struct CommentTree {
+    pub post: Post,
+    pub comments: Vec<CommentNode>
+}
+
+impl Comment {
+    // Parses the `data` object of a `t1` thing, including its nested replies.
+    pub(crate) fn from_data(data: &Value) -> Result<Self, Error> {
+        Ok(Comment {
+            id: extract_string_with_error!(data["id"], "data.id"),
+            name: extract_string_with_error!(data["name"], "data.name"),
+            author: extract_string_with_default!(data["author"], "data.author"),
+            body: extract_string_with_default!(data["body"], "data.body"),
+            body_html: extract_string_with_default!(data["body_html"], "data.body_html"),
+            subreddit: extract_string_with_default!(data["subreddit"], "data.subreddit"),
+            link_id: extract_string_with_default!(data["link_id"], "data.link_id"),
+            parent_id: extract_string_with_error!(data["parent_id"], "data.parent_id"),
+            permalink: extract_string_with_default!(data["permalink"], "data.permalink"),
+            score: data["score"].as_i64().unwrap_or_default(),
+            depth: data["depth"].as_u64().unwrap_or_default() as u32,
+            created_utc: data["created_utc"].as_f64().unwrap_or_default(),
+            likes: data["likes"].as_bool(),
+            // Reddit sends an empty string instead of a listing when there are no replies.
+            replies: match data["replies"].is_object() {
+                true => parse_comment_nodes(&data["replies"]["data"]["children"])?,
+                false => Vec::new()
+            }
+        })
+    }
+}
+
+impl MoreComments {
+    // Parses the `data` object of a `more` thing.
+    pub(crate) fn from_data(data: &Value) -> Result<Self, Error> {
+        Ok(MoreComments {
+            id: extract_string_with_error!(data["id"], "data.id"),
+            name: extract_string_with_error!(data["name"], "data.name"),
+            parent_id: extract_string_with_error!(data["parent_id"], "data.parent_id"),
+            count: data["count"].as_u64().unwrap_or_default(),
+            depth: data["depth"].as_u64().unwrap_or_default() as u32,
+            children: data["children"].as_array()
+                .map_or_else(Vec::new, |children| {
+                    children.iter().filter_map(|c| { c.as_str().map(|s| { s.to_string() }) }).collect()
+                })
+        })
+    }
+}
+
+impl CommentNode {
+    pub(crate) fn from_value(v: &Value) -> Result<Self, Error> {
+        match v["kind"].as_str() {
+            Some("t1") => Ok(CommentNode::Comment(Comment::from_data(&v["data"])?)),
+            Some("more") => Ok(CommentNode::More(MoreComments::from_data(&v["data"])?)),
+            kind => Err(Error::InternalError(format!("Unexpected kind in comment tree: {:?}", kind)))
+        }
+    }
+}
+
+pub(crate) fn parse_comment_nodes(children: &Value) -> Result<Vec<CommentNode>, Error> {
+    children.as_array()
+        .ok_or(Error::InternalError("Can't convert to vector".to_string()))?
+        .iter()
+        .map(CommentNode::from_value)
+        .collect()
+}
+
+impl CommentTree {
+    // Parses the two listings returned by /comments/{article}: the submission followed by its
+    // comments.
+    pub(crate) fn from_value(v: &Value) -> Result<Self, Error> {
+        Ok(CommentTree {
+            post: Post::from_data(&v[0]["data"]["children"][0]["data"])?,
+            comments: parse_comment_nodes(&v[1]["data"]["children"])?
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct CommentsRequest {
+    article: String,
+    subreddit: Option<String>,
+    sort: Option<CommentSort>,
+    depth: Option<u32>,
+    limit: Option<u32>,
+    context: Option<u32>,
+    comment: Option<String>
+}
+
+#[async_trait]
+impl Request<CommentTree> for CommentsRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        let mut query_params: Vec<(&str, String)> = Vec::new();
+
+        if let Some(sort) = &self.sort {
+            query_params.push(("sort", sort.as_str().to_string()));
+        }
+
+        if let Some(depth) = self.depth {
+            query_params.push(("depth", depth.to_string()));
+        }
+
+        if let Some(limit) = self.limit {
+            query_params.push(("limit", limit.to_string()));
+        }
+
+        if let Some(context) = self.context {
+            query_params.push(("context", context.to_string()));
+        }
+
+        if let Some(comment) = &self.comment {
+            query_params.push(("comment", comment.clone()));
+        }
+
+        let path = match &self.subreddit {
+            Some(subreddit) => format!("r/{}/comments/{}", subreddit, self.article),
+            None => format!("comments/{}", self.article)
+        };
+
+        Ok(client.http_client
+            .get(format!("{}{}", REDDIT_API_URL, path))
+            .bearer_auth(client.access_token()?)
+            .query(&query_params))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<CommentTree, Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        CommentTree::from_value(&v)
+    }
+}
+
+pub struct CommentsRequestBuilder {
+    req: CommentsRequest
+}
+
+impl CommentsRequestBuilder {
+    // `article` is the post ID, with or without the `t3_` prefix.
+    pub fn new(article: &str) -> Self {
+        CommentsRequestBuilder {
+            req: CommentsRequest {
+                article: article.trim_start_matches("t3_").to_string(),
+                subreddit: None,
+                sort: None,
+                depth: None,
+                limit: None,
+                context: None,
+                comment: None
+            }
+        }
+    }
+
+    pub fn subreddit(mut self, subreddit: &str) -> Self {
+        self.req.subreddit = Some(subreddit.to_string());
+        self
+    }
+
+    pub fn sort(mut self, sort: CommentSort) -> Self {
+        self.req.sort = Some(sort);
+        self
+    }
+
+    pub fn depth(mut self, depth: u32) -> Self {
+        self.req.depth = Some(depth);
+        self
+    }
+
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.req.limit = Some(limit);
+        self
+    }
+
+    // Number of parent comments to include above the focused comment, 0 to 8.
+    pub fn context(mut self, context: u32) -> Result<Self, Error> {
+        if context > 8 {
+            return Err(Error::UserError(format!("Context must be between 0 and 8: {}", context)));
+        }
+        self.req.context = Some(context);
+        Ok(self)
+    }
+
+    // Focuses the tree on a single comment, given its ID without the `t1_` prefix.
+    pub fn comment(mut self, comment: &str) -> Self {
+        self.req.comment = Some(comment.trim_start_matches("t1_").to_string());
+        self
+    }
+}
+
+impl RequestBuilder<CommentsRequest> for CommentsRequestBuilder {
+    fn build(&self) -> CommentsRequest {
+        self.req.clone()
+    }
+}
+
+// Reddit refuses to load more than this many children in a single /api/morechildren call.
+const MORE_CHILDREN_LIMIT: usize = 100;
+
+impl CommentTree {
+    // Replaces the `more` stub with ID `stub_id` by the flat list of comments loaded for it,
+    // nesting each loaded comment under its parent. Returns false if the stub is not in the tree.
+    pub fn splice(&mut self, stub_id: &str, loaded: Vec<CommentNode>) -> bool {
+        let stub = match find_more(&self.comments, stub_id) {
+            Some(stub) => stub.clone(),
+            None => { return false; }
+        };
+
+        // Replies of the loaded comments can come back too, so they no longer need loading.
+        let loaded_ids: Vec<String> = loaded.iter()
+            .filter_map(|node| {
+                match node {
+                    CommentNode::Comment(comment) => Some(comment.id.clone()),
+                    CommentNode::More(_) => None
+                }
+            })
+            .collect();
+        let mut nodes = nest_comment_nodes(loaded, &stub.parent_id);
+
+        // Keep whatever could not be loaded in one call as a stub in the same place. Its count is
+        // the number of IDs left, as Reddit's count also covers replies that have been loaded.
+        let loaded_count = stub.children.len().min(MORE_CHILDREN_LIMIT);
+        let remaining: Vec<String> = stub.children[loaded_count..].iter()
+            .filter(|id| { !loaded_ids.contains(id) })
+            .cloned()
+            .collect();
+        if !remaining.is_empty() {
+            nodes.push(CommentNode::More(MoreComments {
+                count: remaining.len() as u64,
+                children: remaining,
+                ..stub
+            }));
+        }
+
+        replace_more(&mut self.comments, stub_id, &mut Some(nodes))
+    }
+
+    // Loads the children of a single `more` stub and splices them into the tree.
+    pub async fn expand_more(&mut self, client: &RedditClient, stub_id: &str) -> Result<(), Error> {
+        let stub = find_more(&self.comments, stub_id)
+            .ok_or(Error::UserError(format!("No more stub with ID in tree: {}", stub_id)))?;
+        if stub.children.is_empty() {
+            return Err(Error::UserError(format!("More stub has no children to load: {}", stub_id)));
+        }
+
+        let loaded = MoreChildrenRequestBuilder::new(
+            &self.post.name,
+            stub.children.iter().take(MORE_CHILDREN_LIMIT).cloned().collect()
+            )?
+            .build()
+            .send(client)
+            .await?;
+
+        self.splice(stub_id, loaded);
+        Ok(())
+    }
+
+    // Expands `more` stubs depth-first until none are left or `budget` requests have been made.
+    // "Continue this thread" stubs are left in place. Returns the number of requests made.
+    pub async fn expand_all(&mut self, client: &RedditClient, budget: u32) -> Result<u32, Error> {
+        let mut requests = 0;
+        while requests < budget {
+            let stub_id = match first_expandable_more(&self.comments) {
+                Some(stub) => stub.id.clone(),
+                None => { break; }
+            };
+            self.expand_more(client, &stub_id).await?;
+            requests += 1;
+        }
+        Ok(requests)
+    }
+}
+
+fn find_more<'a>(nodes: &'a [CommentNode], stub_id: &str) -> Option<&'a MoreComments> {
+    nodes.iter().find_map(|node| {
+        match node {
+            CommentNode::More(more) if more.id == stub_id => Some(more),
+            CommentNode::More(_) => None,
+            CommentNode::Comment(comment) => find_more(&comment.replies, stub_id)
+        }
+    })
+}
+
+fn first_expandable_more(nodes: &[CommentNode]) -> Option<&MoreComments> {
+    nodes.iter().find_map(|node| {
+        match node {
+            CommentNode::More(more) if !more.children.is_empty() => Some(more),
+            CommentNode::More(_) => None,
+            CommentNode::Comment(comment) => first_expandable_more(&comment.replies)
+        }
+    })
+}
+
+fn replace_more(nodes: &mut Vec<CommentNode>,
+                stub_id: &str,
+                replacement: &mut Option<Vec<CommentNode>>) -> bool {
+    let position = nodes.iter().position(|node| {
+        matches!(node, CommentNode::More(more) if more.id == stub_id)
+    });
+
+    if let Some(position) = position {
+        if let Some(replacement) = replacement.take() {
+            nodes.splice(position..position + 1, replacement);
+        }
+        return true;
+    }
+
+    nodes.iter_mut().any(|node| {
+        match node {
+            CommentNode::Comment(comment) => replace_more(&mut comment.replies, stub_id, replacement),
+            CommentNode::More(_) => false
+        }
+    })
+}
+
+// /api/morechildren returns comments flattened depth-first, so every parent is seen before its
+// replies. Nodes whose parent cannot be found are kept at the top level.
+fn nest_comment_nodes(flat: Vec<CommentNode>, root_parent_id: &str) -> Vec<CommentNode> {
+    let mut nested: Vec<CommentNode> = Vec::new();
+    for node in flat {
+        let parent_id = match &node {
+            CommentNode::Comment(comment) => comment.parent_id.clone(),
+            CommentNode::More(more) => more.parent_id.clone()
+        };
+        match find_comment_mut(&mut nested, &parent_id) {
+            Some(parent) if parent_id != root_parent_id => parent.replies.push(node),
+            _ => nested.push(node)
+        }
+    }
+    nested
+}
+
+fn find_comment_mut<'a>(nodes: &'a mut [CommentNode], name: &str) -> Option<&'a mut Comment> {
+    for node in nodes.iter_mut() {
+        if let CommentNode::Comment(comment) = node {
+            if comment.name == name {
+                return Some(comment);
+            }
+            if let Some(found) = find_comment_mut(&mut comment.replies, name) {
+                return Some(found);
+            }
+        }
+    }
+    None
+}
+
+#[derive(Clone)]
+pub struct MoreChildrenRequest {
+    link_id: String,
+    children: Vec<String>,
+    sort: Option<CommentSort>,
+    depth: Option<u32>,
+    limit_children: bool
+}
+
+#[async_trait]
+impl Request<Vec<CommentNode>> for MoreChildrenRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        let mut query_params: Vec<(&str, String)> = vec![
+            ("api_type", "json".to_string()),
+            ("link_id", self.link_id.clone()),
+            ("children", self.children.join(",")),
+            ("limit_children", self.limit_children.to_string())
+        ];
+
+        if let Some(sort) = &self.sort {
+            query_params.push(("sort", sort.as_str().to_string()));
+        }
+
+        if let Some(depth) = self.depth {
+            query_params.push(("depth", depth.to_string()));
+        }
+
+        Ok(client.http_client
+            .get(format!("{}api/morechildren", REDDIT_API_URL))
+            .bearer_auth(client.access_token()?)
+            .query(&query_params))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<Vec<CommentNode>, Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        check_api_errors(&v)?;
+        parse_comment_nodes(&v["json"]["data"]["things"])
+    }
+}
+
+pub struct MoreChildrenRequestBuilder {
+    req: MoreChildrenRequest
+}
+
+impl MoreChildrenRequestBuilder {
+    // `link_id` is the fullname of the post and `children` the IDs listed in a `more` stub, at
+    // most 100 of them.
+    pub fn new(link_id: &str, children: Vec<String>) -> Result<Self, Error> {
+        if children.is_empty() || children.len() > MORE_CHILDREN_LIMIT {
+            return Err(Error::UserError(format!(
+                        "Number of children must be between 1 and {}: {}",
+                        MORE_CHILDREN_LIMIT,
+                        children.len())));
+        }
+        Ok(MoreChildrenRequestBuilder {
+            req: MoreChildrenRequest {
+                link_id: link_id.to_string(),
+                children,
+                sort: None,
+                depth: None,
+                limit_children: false
+            }
+        })
+    }
+
+    pub fn sort(mut self, sort: CommentSort) -> Self {
+        self.req.sort = Some(sort);
+        self
+    }
+
+    pub fn depth(mut self, depth: u32) -> Self {
+        self.req.depth = Some(depth);
+        self
+    }
+
+    // Only return the requested children, not their replies.
+    pub fn limit_children(mut self, limit_children: bool) -> Self {
+        self.req.limit_children = limit_children;
+        self
+    }
+}
+
+impl RequestBuilder<MoreChildrenRequest> for MoreChildrenRequestBuilder {
+    fn build(&self) -> MoreChildrenRequest {
+        self.req.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use serde_json::json;
+    use super::*;
+    use crate::api::util::test_client;
+
+    #[test]
+    fn test_comments_request_url() -> Result<()> {
+        let req = CommentsRequestBuilder::new("t3_abc")
+            .subreddit("rust")
+            .sort(CommentSort::Top)
+            .comment("t1_def")
+            .context(3)?
+            .build()
+            .construct(&test_client())?;
+
+        assert_eq!(req.url().path(), "/r/rust/comments/abc");
+        assert_eq!(req.url().query(), Some("sort=top&context=3&comment=def"));
+        assert!(CommentsRequestBuilder::new("abc").context(9).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_comment_tree() -> Result<()> {
+        let v = json!([
+            { "kind": "Listing", "data": { "children": [
+                { "kind": "t3", "data": {
+                    "id": "abc", "name": "t3_abc", "subreddit": "rust", "title": "Title",
+                    "selftext": "", "score": -2
+                } }
+            ] } },
+            { "kind": "Listing", "data": { "children": [
+                { "kind": "t1", "data": {
+                    "id": "c1", "name": "t1_c1", "parent_id": "t3_abc", "body": "Top", "score": 5, "likes": true,
+                    "replies": { "kind": "Listing", "data": { "children": [
+                        { "kind": "t1", "data": {
+                            "id": "c2", "name": "t1_c2", "parent_id": "t1_c1", "body": "Reply",
+                            "depth": 1, "replies": ""
+                        } }
+                    ] } }
+                } },
+                { "kind": "more", "data": {
+                    "id": "c3", "name": "t1_c3", "parent_id": "t3_abc", "count": 2,
+                    "children": ["c3", "c4"]
+                } }
+            ] } }
+        ]);
+
+        let tree = CommentTree::from_value(&v)?;
+        assert_eq!(tree.post.name, "t3_abc");
+        assert_eq!(tree.post.score, -2);
+        assert_eq!(tree.comments.len(), 2);
+        match &tree.comments[0] {
+            CommentNode::Comment(c) => {
+                assert_eq!(c.body, "Top");
+                assert_eq!(c.likes, Some(true));
+                assert_eq!(c.replies.len(), 1);
+            },
+            node => panic!("Expected comment, got {:?}", node)
+        }
+        match &tree.comments[1] {
+            CommentNode::More(m) => assert_eq!(m.children, vec!["c3", "c4"]),
+            node => panic!("Expected more stub, got {:?}", node)
+        }
+        Ok(())
+    }
+
+    fn comment(id: &str, parent_id: &str) -> CommentNode {
+        CommentNode::Comment(Comment {
+            id: id.to_string(),
+            name: format!("t1_{}", id),
+            parent_id: parent_id.to_string(),
+            ..Default::default()
+        })
+    }
+
+    fn ids(nodes: &[CommentNode]) -> Vec<String> {
+        nodes.iter().map(|node| {
+            match node {
+                CommentNode::Comment(c) => c.id.clone(),
+                CommentNode::More(m) => format!("more:{}", m.id)
+            }
+        }).collect()
+    }
+
+    #[test]
+    fn test_splice_more_children() -> Result<()> {
+        let mut top = comment("a", "t3_p");
+        if let CommentNode::Comment(c) = &mut top {
+            c.replies.push(comment("b", "t1_a"));
+            c.replies.push(CommentNode::More(MoreComments {
+                id: "m".to_string(),
+                name: "t1_m".to_string(),
+                parent_id: "t1_a".to_string(),
+                count: 3,
+                children: vec!["c".to_string(), "d".to_string()],
+                ..Default::default()
+            }));
+        }
+        let mut tree = CommentTree {
+            post: Post { name: "t3_p".to_string(), ..Default::default() },
+            comments: vec![top, comment("z", "t3_p")]
+        };
+
+        assert!(tree.splice("m", vec![comment("c", "t1_a"), comment("e", "t1_c"), comment("d", "t1_a")]));
+        assert!(!tree.splice("m", Vec::new()));
+
+        assert_eq!(ids(&tree.comments), vec!["a", "z"]);
+        match &tree.comments[0] {
+            CommentNode::Comment(a) => {
+                assert_eq!(ids(&a.replies), vec!["b", "c", "d"]);
+                match &a.replies[1] {
+                    CommentNode::Comment(c) => assert_eq!(ids(&c.replies), vec!["e"]),
+                    node => panic!("Expected comment, got {:?}", node)
+                }
+            },
+            node => panic!("Expected comment, got {:?}", node)
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_splice_keeps_remaining_children() -> Result<()> {
+        let children: Vec<String> = (0..150).map(|i| { i.to_string() }).collect();
+        let mut tree = CommentTree {
+            post: Post::default(),
+            comments: vec![CommentNode::More(MoreComments {
+                id: "m".to_string(),
+                parent_id: "t3_p".to_string(),
+                count: 150,
+                children: children.clone(),
+                ..Default::default()
+            })]
+        };
+
+        assert!(tree.splice("m", vec![comment("0", "t3_p")]));
+        assert_eq!(ids(&tree.comments), vec!["0", "more:m"]);
+        match &tree.comments[1] {
+            CommentNode::More(m) => {
+                assert_eq!(m.children, children[100..].to_vec());
+                assert_eq!(m.count, 50);
+            },
+            node => panic!("Expected more stub, got {:?}", node)
+        }
+        assert!(MoreChildrenRequestBuilder::new("t3_p", children).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_splice_count_with_nested_replies() -> Result<()> {
+        let children: Vec<String> = (0..150).map(|i| { i.to_string() }).collect();
+        let mut tree = CommentTree {
+            post: Post::default(),
+            comments: vec![CommentNode::More(MoreComments {
+                id: "m".to_string(),
+                parent_id: "t3_p".to_string(),
+                count: 300,
+                children,
+                ..Default::default()
+            })]
+        };
+
+        // "120" is a reply to "0" and was loaded with it, even though it was past the first 100.
+        assert!(tree.splice("m", vec![comment("0", "t3_p"), comment("120", "t1_0"), comment("121", "t1_120")]));
+        assert_eq!(ids(&tree.comments), vec!["0", "more:m"]);
+        match &tree.comments[0] {
+            CommentNode::Comment(c) => assert_eq!(ids(&c.replies), vec!["120"]),
+            node => panic!("Expected comment, got {:?}", node)
+        }
+        match &tree.comments[1] {
+            CommentNode::More(m) => {
+                assert_eq!(m.children.len(), 48);
+                assert!(!m.children.contains(&"120".to_string()));
+                assert_eq!(m.count, 48);
+            },
+            node => panic!("Expected more stub, got {:?}", node)
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,588 @@
+use reqwest::RequestBuilder as HTTPRequestBuilder;
+use async_trait::async_trait;
+use serde_json::Value;
+
+use super::util::{
+    check_api_errors,
+    extract_string_with_default,
+    extract_string_with_error,
+    send_for_json,
+    send_for_json_chunks,
+    single_chunk,
+    Request,
+    RequestBuilder,
+    Error
+};
+use crate::REDDIT_API_URL;
+use super::util::RedditClient;
+
+// Reddit caps the number of lines of a single flair CSV upload.
+const MAX_CSV_LINES_PER_REQUEST: usize = 100;
+const MAX_FLAIR_TEXT_LENGTH: usize = 64;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FlairType {
+    User,
+    // Flair of posts.
+    Link
+}
+
+impl FlairType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            FlairType::User => "USER_FLAIR",
+            FlairType::Link => "LINK_FLAIR"
+        }
+    }
+
+    fn templates_path(&self) -> &'static str {
+        match self {
+            FlairType::User => "user_flair_v2",
+            FlairType::Link => "link_flair_v2"
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum FlairTextColor {
+    Light,
+    Dark
+}
+
+impl FlairTextColor {
+    fn as_str(&self) -> &'static str {
+        match self {
+            FlairTextColor::Light => "light",
+            FlairTextColor::Dark => "dark"
+        }
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct FlairTemplate {
+    pub id: String,
+    pub text: String,
+    // Whether users may change the text when selecting the template.
+    pub text_editable: bool,
+    pub css_class: String,
+    // Hex color, e.g. `#ff4500`, or empty.
+    pub background_color: String,
+    // `light` or `dark`.
+    pub text_color: String,
+    // Whether only moderators may assign the template.
+    pub mod_only: bool,
+    pub max_emojis: u64,
+    // `text` or `richtext`.
+    pub flair_type: String
+}
+
+impl FlairTemplate {
+    // Parses a template object as returned by the `*_flair_v2` and `flairtemplate_v2` endpoints.
+    pub(crate) fn from_value(v: &Value) -> Result<Self, Error> {
+        Ok(FlairTemplate {
+            id: extract_string_with_error!(v["id"], "id"),
+            text: extract_string_with_default!(v["text"], "text"),
+            text_editable: v["text_editable"].as_bool().unwrap_or_default(),
+            css_class: extract_string_with_default!(v["css_class"], "css_class"),
+            background_color: extract_string_with_default!(v["background_color"], "background_color"),
+            text_color: extract_string_with_default!(v["text_color"], "text_color"),
+            mod_only: v["mod_only"].as_bool().unwrap_or_default(),
+            max_emojis: v["max_emojis"].as_u64().unwrap_or_default(),
+            flair_type: extract_string_with_default!(v["type"], "type")
+        })
+    }
+}
+
+// Flair templates of a subreddit that the current user may choose from.
+#[derive(Clone)]
+pub struct FlairTemplatesRequest {
+    subreddit: String,
+    flair_type: FlairType
+}
+
+impl FlairTemplatesRequest {
+    pub fn new(subreddit: &str, flair_type: FlairType) -> Self {
+        FlairTemplatesRequest {
+            subreddit: subreddit.to_string(),
+            flair_type
+        }
+    }
+}
+
+#[async_trait]
+impl Request<Vec<FlairTemplate>> for FlairTemplatesRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        Ok(client.http_client
+            .get(format!("{}r/{}/api/{}", REDDIT_API_URL, self.subreddit, self.flair_type.templates_path()))
+            .bearer_auth(client.access_token()?))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<Vec<FlairTemplate>, Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        v.as_array()
+            .ok_or(Error::InternalError("Can't convert to vector".to_string()))?
+            .iter()
+            .map(FlairTemplate::from_value)
+            .collect()
+    }
+}
+
+#[derive(Clone)]
+enum FlairTarget {
+    User(String),
+    Link(String)
+}
+
+#[derive(Clone)]
+pub struct SelectFlairRequest {
+    subreddit: String,
+    target: FlairTarget,
+    template_id: Option<String>,
+    text: Option<String>
+}
+
+#[async_trait]
+impl Request<()> for SelectFlairRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        let mut form_params: Vec<(&str, String)> = vec![("api_type", "json".to_string())];
+
+        match &self.target {
+            FlairTarget::User(name) => form_params.push(("name", name.clone())),
+            FlairTarget::Link(link) => form_params.push(("link", link.clone()))
+        }
+
+        if let Some(template_id) = &self.template_id {
+            form_params.push(("flair_template_id", template_id.clone()));
+        }
+
+        if let Some(text) = &self.text {
+            form_params.push(("text", text.clone()));
+        }
+
+        Ok(client.http_client
+            .post(format!("{}r/{}/api/selectflair", REDDIT_API_URL, self.subreddit))
+            .bearer_auth(client.access_token()?)
+            .form(&form_params))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<(), Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        check_api_errors(&v)
+    }
+}
+
+pub struct SelectFlairRequestBuilder {
+    req: SelectFlairRequest
+}
+
+impl SelectFlairRequestBuilder {
+    // Sets the flair of `username` in `subreddit`. Users may only set their own flair unless they
+    // are moderators. Without a template or text the flair is removed.
+    pub fn user(subreddit: &str, username: &str) -> Self {
+        SelectFlairRequestBuilder::with_target(subreddit, FlairTarget::User(username.to_string()))
+    }
+
+    // Sets the flair of the post with fullname `link`.
+    pub fn post(subreddit: &str, link: &str) -> Result<Self, Error> {
+        if !link.starts_with("t3_") {
+            return Err(Error::UserError(format!("Not a post fullname: {}", link)));
+        }
+        Ok(SelectFlairRequestBuilder::with_target(subreddit, FlairTarget::Link(link.to_string())))
+    }
+
+    fn with_target(subreddit: &str, target: FlairTarget) -> Self {
+        SelectFlairRequestBuilder {
+            req: SelectFlairRequest {
+                subreddit: subreddit.to_string(),
+                target,
+                template_id: None,
+                text: None
+            }
+        }
+    }
+
+    pub fn template(mut self, template_id: &str) -> Self {
+        self.req.template_id = Some(template_id.to_string());
+        self
+    }
+
+    // Custom text, only allowed for templates with editable text or by moderators.
+    pub fn text(mut self, text: &str) -> Result<Self, Error> {
+        if text.chars().count() > MAX_FLAIR_TEXT_LENGTH {
+            return Err(Error::UserError(format!(
+                        "Flair text cannot be longer than {} characters.",
+                        MAX_FLAIR_TEXT_LENGTH)));
+        }
+        self.req.text = Some(text.to_string());
+        Ok(self)
+    }
+}
+
+impl RequestBuilder<SelectFlairRequest> for SelectFlairRequestBuilder {
+    fn build(&self) -> SelectFlairRequest {
+        self.req.clone()
+    }
+}
+
+// Creates or edits a flair template. Requires moderator permissions.
+#[derive(Clone)]
+pub struct FlairTemplateRequest {
+    subreddit: String,
+    flair_type: FlairType,
+    template_id: Option<String>,
+    text: Option<String>,
+    text_editable: Option<bool>,
+    css_class: Option<String>,
+    background_color: Option<String>,
+    text_color: Option<FlairTextColor>,
+    mod_only: Option<bool>,
+    max_emojis: Option<u32>
+}
+
+#[async_trait]
+impl Request<FlairTemplate> for FlairTemplateRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        let mut form_params: Vec<(&str, String)> = vec![
+            ("api_type", "json".to_string()),
+            ("flair_type", self.flair_type.as_str().to_string())
+        ];
+
+        if let Some(template_id) = &self.template_id {
+            form_params.push(("flair_template_id", template_id.clone()));
+        }
+
+        if let Some(text) = &self.text {
+            form_params.push(("text", text.clone()));
+        }
+
+        if let Some(text_editable) = self.text_editable {
+            form_params.push(("text_editable", text_editable.to_string()));
+        }
+
+        if let Some(css_class) = &self.css_class {
+            form_params.push(("css_class", css_class.clone()));
+        }
+
+        if let Some(background_color) = &self.background_color {
+            form_params.push(("background_color", background_color.clone()));
+        }
+
+        if let Some(text_color) = &self.text_color {
+            form_params.push(("text_color", text_color.as_str().to_string()));
+        }
+
+        if let Some(mod_only) = self.mod_only {
+            form_params.push(("mod_only", mod_only.to_string()));
+        }
+
+        if let Some(max_emojis) = self.max_emojis {
+            form_params.push(("max_emojis", max_emojis.to_string()));
+        }
+
+        Ok(client.http_client
+            .post(format!("{}r/{}/api/flairtemplate_v2", REDDIT_API_URL, self.subreddit))
+            .bearer_auth(client.access_token()?)
+            .form(&form_params))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<FlairTemplate, Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        check_api_errors(&v)?;
+        FlairTemplate::from_value(&v)
+    }
+}
+
+pub struct FlairTemplateRequestBuilder {
+    req: FlairTemplateRequest
+}
+
+impl FlairTemplateRequestBuilder {
+    pub fn create(subreddit: &str, flair_type: FlairType) -> Self {
+        FlairTemplateRequestBuilder {
+            req: FlairTemplateRequest {
+                subreddit: subreddit.to_string(),
+                flair_type,
+                template_id: None,
+                text: None,
+                text_editable: None,
+                css_class: None,
+                background_color: None,
+                text_color: None,
+                mod_only: None,
+                max_emojis: None
+            }
+        }
+    }
+
+    // Changes the fields that are set on the existing template with id `template_id`.
+    pub fn edit(subreddit: &str, flair_type: FlairType, template_id: &str) -> Self {
+        let mut builder = FlairTemplateRequestBuilder::create(subreddit, flair_type);
+        builder.req.template_id = Some(template_id.to_string());
+        builder
+    }
+
+    pub fn text(mut self, text: &str) -> Result<Self, Error> {
+        if text.chars().count() > MAX_FLAIR_TEXT_LENGTH {
+            return Err(Error::UserError(format!(
+                        "Flair text cannot be longer than {} characters.",
+                        MAX_FLAIR_TEXT_LENGTH)));
+        }
+        self.req.text = Some(text.to_string());
+        Ok(self)
+    }
+
+    pub fn text_editable(mut self, text_editable: bool) -> Self {
+        self.req.text_editable = Some(text_editable);
+        self
+    }
+
+    pub fn css_class(mut self, css_class: &str) -> Self {
+        self.req.css_class = Some(css_class.to_string());
+        self
+    }
+
+    // Hex color, e.g. `#ff4500`.
+    pub fn background_color(mut self, background_color: &str) -> Self {
+        self.req.background_color = Some(background_color.to_string());
+        self
+    }
+
+    pub fn text_color(mut self, text_color: FlairTextColor) -> Self {
+        self.req.text_color = Some(text_color);
+        self
+    }
+
+    pub fn mod_only(mut self, mod_only: bool) -> Self {
+        self.req.mod_only = Some(mod_only);
+        self
+    }
+
+    pub fn max_emojis(mut self, max_emojis: u32) -> Self {
+        self.req.max_emojis = Some(max_emojis);
+        self
+    }
+}
+
+impl RequestBuilder<FlairTemplateRequest> for FlairTemplateRequestBuilder {
+    fn build(&self) -> FlairTemplateRequest {
+        self.req.clone()
+    }
+}
+
+// Deletes a flair template. Requires moderator permissions.
+#[derive(Clone)]
+pub struct DeleteFlairTemplateRequest {
+    subreddit: String,
+    template_id: String
+}
+
+impl DeleteFlairTemplateRequest {
+    pub fn new(subreddit: &str, template_id: &str) -> Self {
+        DeleteFlairTemplateRequest {
+            subreddit: subreddit.to_string(),
+            template_id: template_id.to_string()
+        }
+    }
+}
+
+#[async_trait]
+impl Request<()> for DeleteFlairTemplateRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        Ok(client.http_client
+            .post(format!("{}r/{}/api/deleteflairtemplate", REDDIT_API_URL, self.subreddit))
+            .bearer_auth(client.access_token()?)
+            .form(&[("api_type", "json"), ("flair_template_id", &self.template_id)]))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<(), Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        check_api_errors(&v)
+    }
+}
+
+// One line of a flair CSV upload. Empty text and CSS class remove the user's flair.
+#[derive(Clone, Debug)]
+pub struct FlairAssignment {
+    pub username: String,
+    pub text: String,
+    pub css_class: String
+}
+
+impl FlairAssignment {
+    pub fn new(username: &str, text: &str, css_class: &str) -> Self {
+        FlairAssignment {
+            username: username.to_string(),
+            text: text.to_string(),
+            css_class: css_class.to_string()
+        }
+    }
+
+    fn csv_line(&self) -> String {
+        [&self.username, &self.text, &self.css_class].iter()
+            .map(|field| { format!("\"{}\"", field.replace('"', "\"\"")) })
+            .collect::<Vec<String>>()
+            .join(",")
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct FlairCsvResult {
+    pub ok: bool,
+    // Description of what was done, e.g. `added flair for user bob`.
+    pub status: String,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>
+}
+
+impl FlairCsvResult {
+    pub(crate) fn from_value(v: &Value) -> Self {
+        let messages = |v: &Value| -> Vec<String> {
+            v.as_object().map_or_else(Vec::new, |messages| {
+                messages.values()
+                    .filter_map(|message| { message.as_str().map(|message| { message.to_string() }) })
+                    .collect()
+            })
+        };
+
+        FlairCsvResult {
+            ok: v["ok"].as_bool().unwrap_or_default(),
+            status: extract_string_with_default!(v["status"], "status"),
+            errors: messages(&v["errors"]),
+            warnings: messages(&v["warnings"])
+        }
+    }
+}
+
+// Assigns user flair in bulk. Requires moderator permissions.
+#[derive(Clone)]
+pub struct FlairCsvRequest {
+    subreddit: String,
+    assignments: Vec<FlairAssignment>
+}
+
+impl FlairCsvRequest {
+    // `send` splits the assignments into uploads of at most 100 lines and returns one result per
+    // assignment; `construct` only accepts up to 100.
+    pub fn new(subreddit: &str, assignments: Vec<FlairAssignment>) -> Result<Self, Error> {
+        if assignments.is_empty() {
+            return Err(Error::UserError("No flair to assign.".to_string()));
+        }
+        Ok(FlairCsvRequest {
+            subreddit: subreddit.to_string(),
+            assignments
+        })
+    }
+
+    fn chunk_builder(&self, client: &RedditClient, chunk: &[FlairAssignment]) -> Result<HTTPRequestBuilder, Error> {
+        let flair_csv = chunk.iter()
+            .map(FlairAssignment::csv_line)
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        Ok(client.http_client
+            .post(format!("{}r/{}/api/flaircsv", REDDIT_API_URL, self.subreddit))
+            .bearer_auth(client.access_token()?)
+            .form(&[("flair_csv", flair_csv)]))
+    }
+}
+
+#[async_trait]
+impl Request<Vec<FlairCsvResult>> for FlairCsvRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        self.chunk_builder(client, single_chunk(&self.assignments, MAX_CSV_LINES_PER_REQUEST)?)
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<Vec<FlairCsvResult>, Error> {
+        let mut results: Vec<FlairCsvResult> = Vec::new();
+        let responses = send_for_json_chunks(&self.assignments, MAX_CSV_LINES_PER_REQUEST, |chunk| {
+            self.chunk_builder(client, chunk)
+        }).await?;
+        for v in responses {
+            results.extend(v.as_array()
+                .ok_or(Error::InternalError("Can't convert to vector".to_string()))?
+                .iter()
+                .map(FlairCsvResult::from_value));
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use serde_json::json;
+    use super::*;
+    use crate::api::util::{ test_body, test_client };
+
+    #[test]
+    fn test_flair_requests() -> Result<()> {
+        let client = test_client();
+
+        let req = FlairTemplatesRequest::new("rust", FlairType::Link).construct(&client)?;
+        assert_eq!(req.url().path(), "/r/rust/api/link_flair_v2");
+
+        let req = SelectFlairRequestBuilder::post("rust", "t3_abc")?
+            .template("0b5e9f3a")
+            .text("Solved")?
+            .build()
+            .construct(&client)?;
+        assert_eq!(req.url().path(), "/r/rust/api/selectflair");
+        assert_eq!(test_body(&req), "api_type=json&link=t3_abc&flair_template_id=0b5e9f3a&text=Solved");
+        assert!(SelectFlairRequestBuilder::post("rust", "t1_abc").is_err());
+
+        let req = FlairTemplateRequestBuilder::edit("rust", FlairType::User, "0b5e9f3a")
+            .text("Rustacean")?
+            .text_color(FlairTextColor::Light)
+            .mod_only(true)
+            .build()
+            .construct(&client)?;
+        assert_eq!(req.url().path(), "/r/rust/api/flairtemplate_v2");
+        assert_eq!(
+            test_body(&req),
+            "api_type=json&flair_type=USER_FLAIR&flair_template_id=0b5e9f3a&text=Rustacean&text_color=light&mod_only=true"
+        );
+
+        let req = DeleteFlairTemplateRequest::new("rust", "0b5e9f3a").construct(&client)?;
+        assert_eq!(req.url().path(), "/r/rust/api/deleteflairtemplate");
+        Ok(())
+    }
+
+    #[test]
+    fn test_flair_csv() -> Result<()> {
+        let client = test_client();
+
+        let req = FlairCsvRequest::new("rust", vec![
+            FlairAssignment::new("bob", "Says \"hi\"", "greeter"),
+            FlairAssignment::new("alice", "", "")
+        ])?.construct(&client)?;
+        assert_eq!(req.url().path(), "/r/rust/api/flaircsv");
+        let flair_csv = url::form_urlencoded::parse(test_body(&req).as_bytes())
+            .map(|(_, flair_csv)| { flair_csv.to_string() })
+            .collect::<String>();
+        assert_eq!(flair_csv, "\"bob\",\"Says \"\"hi\"\"\",\"greeter\"\n\"alice\",\"\",\"\"");
+
+        let assignments = vec![FlairAssignment::new("bob", "", ""); MAX_CSV_LINES_PER_REQUEST + 1];
+        assert!(FlairCsvRequest::new("rust", assignments)?.construct(&client).is_err());
+        assert!(FlairCsvRequest::new("rust", Vec::new()).is_err());
+
+        let result = FlairCsvResult::from_value(&json!({
+            "ok": false, "status": "skipped", "errors": { "user": "unable to resolve user `bob'" }, "warnings": {}
+        }));
+        assert!(!result.ok);
+        assert_eq!(result.errors, vec!["unable to resolve user `bob'"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_flair_templates() -> Result<()> {
+        let template = FlairTemplate::from_value(&json!({
+            "id": "0b5e9f3a", "text": "Rustacean", "text_editable": true, "css_class": "",
+            "background_color": "#ff4500", "text_color": "light", "mod_only": false,
+            "max_emojis": 10, "type": "richtext", "richtext": []
+        }))?;
+        assert_eq!(template.text, "Rustacean");
+        assert!(template.text_editable);
+        assert_eq!(template.flair_type, "richtext");
+        Ok(())
+    }
+}
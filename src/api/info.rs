@@ -0,0 +1,288 @@
+use std::collections::HashMap;
+
+use reqwest::RequestBuilder as HTTPRequestBuilder;
+use async_trait::async_trait;
+use serde_json::Value;
+
+use super::util::{ send_for_json, send_for_json_chunks, single_chunk, Request, Error };
+use super::listing::Post;
+use super::thing::ThingListing;
+use crate::REDDIT_API_URL;
+use super::util::RedditClient;
+
+// Reddit caps the number of fullnames that can be looked up in a single request.
+const MAX_NAMES_PER_REQUEST: usize = 100;
+
+#[derive(Default, Debug)]
+pub struct PostsById {
+    // Found posts, in the order their fullnames were given.
+    pub posts: Vec<Post>,
+    // Fullnames Reddit returned nothing for, e.g. deleted or nonexistent posts.
+    pub missing: Vec<String>
+}
+
+#[derive(Clone)]
+pub struct ByIdRequest {
+    names: Vec<String>
+}
+
+impl ByIdRequest {
+    // `names` are post fullnames (`t3_...`). `send` splits them into requests of at most 100
+    // fullnames; `construct` only accepts up to 100.
+    pub fn new(names: Vec<String>) -> Result<Self, Error> {
+        if names.is_empty() {
+            return Err(Error::UserError("No fullnames to look up.".to_string()));
+        }
+        if let Some(name) = names.iter().find(|name| { !name.starts_with("t3_") }) {
+            return Err(Error::UserError(format!("Not a post fullname: {}", name)));
+        }
+        Ok(ByIdRequest { names })
+    }
+
+    fn chunk_builder(&self, client: &RedditClient, chunk: &[String]) -> Result<HTTPRequestBuilder, Error> {
+        Ok(client.http_client
+            .get(format!("{}by_id/{}", REDDIT_API_URL, chunk.join(",")))
+            .bearer_auth(client.access_token()?))
+    }
+}
+
+#[async_trait]
+impl Request<PostsById> for ByIdRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        self.chunk_builder(client, single_chunk(&self.names, MAX_NAMES_PER_REQUEST)?)
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<PostsById, Error> {
+        let mut found: HashMap<String, Post> = HashMap::new();
+        let responses = send_for_json_chunks(&self.names, MAX_NAMES_PER_REQUEST, |chunk| {
+            self.chunk_builder(client, chunk)
+        }).await?;
+        for v in responses {
+            for post in parse_posts(&v)? {
+                found.insert(post.name.clone(), post);
+            }
+        }
+        Ok(order_by_names(&self.names, &found))
+    }
+}
+
+fn parse_posts(v: &Value) -> Result<Vec<Post>, Error> {
+    v["data"]["children"].as_array()
+        .ok_or(Error::InternalError("Can't convert to vector".to_string()))?
+        .iter()
+        .map(|v_post| -> Result<Post, Error> { Post::from_data(&v_post["data"]) })
+        .collect()
+}
+
+fn order_by_names(names: &[String], found: &HashMap<String, Post>) -> PostsById {
+    let mut result = PostsById::default();
+    for name in names {
+        match found.get(name) {
+            Some(post) => result.posts.push(post.clone()),
+            None => result.missing.push(name.clone())
+        }
+    }
+    result
+}
+
+// Reddit's largest page size for URL lookups.
+const MAX_URL_PAGE_SIZE: u32 = 100;
+
+// Fullnames are a `t1_` to `t6_` type prefix followed by a base36 id.
+fn is_fullname(name: &str) -> bool {
+    let bytes = name.as_bytes();
+    bytes.len() > 3 && bytes[0] == b't' && (b'1'..=b'6').contains(&bytes[1]) && bytes[2] == b'_'
+}
+
+#[derive(Clone)]
+enum InfoLookup {
+    Names(Vec<String>),
+    Url(String)
+}
+
+#[derive(Clone)]
+pub struct InfoRequest {
+    lookup: InfoLookup
+}
+
+impl InfoRequest {
+    // Looks up fullnames of any kind, e.g. `t1_...` comments, `t3_...` posts and `t5_...`
+    // subreddits. `send` splits them into requests of at most 100 fullnames; `construct` only
+    // accepts up to 100.
+    pub fn by_names(names: Vec<String>) -> Result<Self, Error> {
+        if names.is_empty() {
+            return Err(Error::UserError("No fullnames to look up.".to_string()));
+        }
+        if let Some(name) = names.iter().find(|name| { !is_fullname(name) }) {
+            return Err(Error::UserError(format!("Not a fullname: {}", name)));
+        }
+        Ok(InfoRequest { lookup: InfoLookup::Names(names) })
+    }
+
+    // Looks up every submission of the given URL. `send` follows `after` until every page has been
+    // fetched; `construct` only builds the request for the first page.
+    pub fn by_url(url: &str) -> Self {
+        InfoRequest { lookup: InfoLookup::Url(url.to_string()) }
+    }
+
+    fn names_builder(&self, client: &RedditClient, chunk: &[String]) -> Result<HTTPRequestBuilder, Error> {
+        Ok(client.http_client
+            .get(format!("{}api/info", REDDIT_API_URL))
+            .bearer_auth(client.access_token()?)
+            .query(&[("id", chunk.join(","))]))
+    }
+
+    fn url_builder(&self, client: &RedditClient, url: &str, after: Option<&str>) -> Result<HTTPRequestBuilder, Error> {
+        let mut builder = client.http_client
+            .get(format!("{}api/info", REDDIT_API_URL))
+            .bearer_auth(client.access_token()?)
+            .query(&[("url", url)])
+            .query(&[("limit", MAX_URL_PAGE_SIZE)]);
+        if let Some(after) = after {
+            builder = builder.query(&[("after", after)]);
+        }
+        Ok(builder)
+    }
+}
+
+// Appends `page` to `listing` and returns the cursor of the next page, if there is one.
+fn append_page(listing: &mut ThingListing, page: ThingListing) -> Option<String> {
+    listing.things.extend(page.things);
+    if page.after.is_empty() {
+        None
+    } else {
+        Some(page.after)
+    }
+}
+
+#[async_trait]
+impl Request<ThingListing> for InfoRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        match &self.lookup {
+            InfoLookup::Names(names) => self.names_builder(client, single_chunk(names, MAX_NAMES_PER_REQUEST)?),
+            InfoLookup::Url(url) => self.url_builder(client, url, None)
+        }
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<ThingListing, Error> {
+        match &self.lookup {
+            InfoLookup::Names(names) => {
+                let mut listing = ThingListing::default();
+                let responses = send_for_json_chunks(names, MAX_NAMES_PER_REQUEST, |chunk| {
+                    self.names_builder(client, chunk)
+                }).await?;
+                for v in responses {
+                    listing.things.extend(ThingListing::from_value(&v)?.things);
+                }
+                Ok(listing)
+            },
+            InfoLookup::Url(url) => {
+                let mut listing = ThingListing::default();
+                let mut after: Option<String> = None;
+                loop {
+                    let v = send_for_json(self.url_builder(client, url, after.as_deref())?).await?;
+                    after = append_page(&mut listing, ThingListing::from_value(&v)?);
+                    if after.is_none() {
+                        break;
+                    }
+                }
+                Ok(listing)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use serde_json::json;
+    use super::*;
+    use crate::api::util::test_client;
+
+    #[test]
+    fn test_by_id_request() -> Result<()> {
+        let names: Vec<String> = (0..150).map(|i| { format!("t3_{}", i) }).collect();
+        let client = test_client();
+
+        assert!(ByIdRequest::new(vec!["t1_a".to_string()]).is_err());
+        assert!(ByIdRequest::new(Vec::new()).is_err());
+        assert!(ByIdRequest::new(names.clone())?.construct(&client).is_err());
+
+        let req = ByIdRequest::new(names[..2].to_vec())?.construct(&client)?;
+        assert_eq!(req.url().path(), "/by_id/t3_0,t3_1");
+        Ok(())
+    }
+
+    #[test]
+    fn test_posts_in_input_order() -> Result<()> {
+        let v = json!({ "kind": "Listing", "data": { "children": [
+            { "kind": "t3", "data": { "id": "b", "name": "t3_b", "subreddit": "rust", "title": "B", "selftext": "", "score": 2 } },
+            { "kind": "t3", "data": { "id": "a", "name": "t3_a", "subreddit": "rust", "title": "A", "selftext": "", "score": 1 } }
+        ] } });
+        let found: HashMap<String, Post> = parse_posts(&v)?.into_iter()
+            .map(|post| { (post.name.clone(), post) })
+            .collect();
+
+        let names = vec!["t3_a".to_string(), "t3_gone".to_string(), "t3_b".to_string()];
+        let result = order_by_names(&names, &found);
+        assert_eq!(result.posts.iter().map(|p| { p.title.as_str() }).collect::<Vec<_>>(), vec!["A", "B"]);
+        assert_eq!(result.missing, vec!["t3_gone"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_info_request() -> Result<()> {
+        let client = test_client();
+
+        assert!(InfoRequest::by_names(vec!["abc".to_string()]).is_err());
+        assert!(InfoRequest::by_names(vec!["t9_a".to_string()]).is_err());
+        assert!(InfoRequest::by_names(vec!["t1_".to_string()]).is_err());
+        assert!(InfoRequest::by_names(Vec::new()).is_err());
+
+        let req = InfoRequest::by_names(vec!["t1_a".to_string(), "t5_b".to_string()])?.construct(&client)?;
+        assert_eq!(req.url().path(), "/api/info");
+        assert_eq!(req.url().query(), Some("id=t1_a%2Ct5_b"));
+
+        let req = InfoRequest::by_url("https://example.com/a?b=c").construct(&client)?;
+        assert_eq!(req.url().query(), Some("url=https%3A%2F%2Fexample.com%2Fa%3Fb%3Dc&limit=100"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_url_lookup_pages() -> Result<()> {
+        let client = test_client();
+        let req = InfoRequest::by_url("https://example.com/");
+        let first = json!({ "kind": "Listing", "data": { "after": "t3_a", "children": [
+            { "kind": "t3", "data": { "id": "a", "name": "t3_a", "subreddit": "rust", "title": "A", "selftext": "", "score": 1 } }
+        ] } });
+        let second = json!({ "kind": "Listing", "data": { "after": null, "children": [
+            { "kind": "t3", "data": { "id": "b", "name": "t3_b", "subreddit": "pics", "title": "B", "selftext": "", "score": 2 } }
+        ] } });
+
+        let mut listing = ThingListing::default();
+        let after = append_page(&mut listing, ThingListing::from_value(&first)?);
+        assert_eq!(after.as_deref(), Some("t3_a"));
+
+        let next = req.url_builder(&client, "https://example.com/", after.as_deref())?.build()?;
+        assert_eq!(next.url().query(), Some("url=https%3A%2F%2Fexample.com%2F&limit=100&after=t3_a"));
+
+        assert_eq!(append_page(&mut listing, ThingListing::from_value(&second)?), None);
+        assert_eq!(listing.posts().map(|p| { p.title.as_str() }).collect::<Vec<_>>(), vec!["A", "B"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_mixed_things() -> Result<()> {
+        let v = json!({ "kind": "Listing", "data": { "after": null, "children": [
+            { "kind": "t1", "data": { "id": "c", "name": "t1_c", "parent_id": "t3_a", "body": "Hi" } },
+            { "kind": "t3", "data": { "id": "a", "name": "t3_a", "subreddit": "rust", "title": "A", "selftext": "", "score": 1 } },
+            { "kind": "t5", "data": { "id": "2fwo", "name": "t5_2fwo", "display_name": "rust", "subscribers": 10 } }
+        ] } });
+
+        let listing = ThingListing::from_value(&v)?;
+        assert_eq!(listing.things.iter().map(|t| { t.name() }).collect::<Vec<_>>(), vec!["t1_c", "t3_a", "t5_2fwo"]);
+        assert_eq!(listing.posts().count(), 1);
+        assert_eq!(listing.comments().next().map(|c| { c.body.as_str() }), Some("Hi"));
+        Ok(())
+    }
+}
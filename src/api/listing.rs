@@ -1,13 +1,19 @@
-use reqwest::{ Request as HTTPRequest,
-    RequestBuilder as HTTPRequestBuilder
-};
+use reqwest::RequestBuilder as HTTPRequestBuilder;
 use async_trait::async_trait;
 use serde::Deserialize;
 use serde_json::Value;
 
-use super::util::{ Request, RequestBuilder, Error };
+use super::util::{
+    extract_string_with_default,
+    extract_string_with_error,
+    send_for_json,
+    Request,
+    RequestBuilder,
+    Error
+};
 use crate::REDDIT_API_URL;
 use super::util::RedditClient;
+use super::multireddit::normalize_multipath;
 
 #[derive(Clone, Debug)]
 pub enum ListingType {
@@ -30,63 +36,71 @@ pub enum SortTime {
     All
 }
 
-#[derive(Clone)]
-pub struct ListingRequest {
-    subreddit: String,
-    listing_type: ListingType,
-    after: Option<String>,
-    before: Option<String>,
-    limit: Option<u32>,
-    g: Option<String>,
-    t: Option<SortTime>
-}
-
-macro_rules! extract_string_with_default {
-    ($a: expr, $b: expr) => {
-        $a.as_str().map_or_else(|| { String::new() }, |v| { v.to_string() })
+impl SortTime {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            SortTime::Hour => "hour",
+            SortTime::Day => "day",
+            SortTime::Week => "week",
+            SortTime::Month => "month",
+            SortTime::Year => "year",
+            SortTime::All => "all"
+        }
     }
 }
 
-macro_rules! extract_string_with_error {
-    ($a: expr, $b: expr) => {
-        $a.as_str().ok_or(Error::InternalError(format!("Failed to parse to string: {}", $b)))?.to_string()
-    }
+// Cursor parameters shared by every listing endpoint.
+#[derive(Clone, Default)]
+pub(crate) struct Pagination {
+    pub(crate) after: Option<String>,
+    pub(crate) before: Option<String>,
+    pub(crate) limit: Option<u32>,
+    pub(crate) count: Option<u32>
 }
 
-#[async_trait]
-impl Request<Listing> for ListingRequest {
-    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
-        let mut query_params: Vec<(&str, &str)> = Vec::new();
-
+impl Pagination {
+    pub(crate) fn push_query_params(&self, query_params: &mut Vec<(&str, String)>) {
         if let Some(after_str) = &self.after {
-            query_params.push(("after", &after_str));
+            query_params.push(("after", after_str.clone()));
         }
-        
+
         if let Some(before_str) = &self.before {
-            query_params.push(("after", &before_str));
+            query_params.push(("before", before_str.clone()));
+        }
+
+        if let Some(limit_u32) = self.limit {
+            query_params.push(("limit", limit_u32.to_string()));
         }
 
-        let limit_str: String;
-        if let Some(limit_u32) = &self.limit {
-            limit_str = limit_u32.to_string();
-            query_params.push(("limit", &limit_str));
+        if let Some(count_u32) = self.count {
+            query_params.push(("count", count_u32.to_string()));
         }
+    }
+}
+
+#[derive(Clone)]
+pub struct ListingRequest {
+    // `r/{subreddit}` or a multireddit path.
+    path: String,
+    listing_type: ListingType,
+    pagination: Pagination,
+    g: Option<String>,
+    t: Option<SortTime>
+}
+
+#[async_trait]
+impl Request<Listing> for ListingRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        let mut query_params: Vec<(&str, String)> = Vec::new();
+
+        self.pagination.push_query_params(&mut query_params);
 
         if let Some(g_str) = &self.g {
-            query_params.push(("g", &g_str));
+            query_params.push(("g", g_str.clone()));
         }
 
         if let Some(t_sort_time) = &self.t {
-            let t_str: &str;
-            match t_sort_time {
-                SortTime::Hour => { t_str = "hour"; },
-                SortTime::Day => { t_str = "day" },
-                SortTime::Week => { t_str = "week" },
-                SortTime::Month => { t_str = "month"},
-                SortTime::Year => { t_str = "year" },
-                SortTime::All => { t_str = "all" }
-            }
-            query_params.push(("t", t_str));
+            query_params.push(("t", t_sort_time.as_str().to_string()));
         }
 
         let listing_string: &str;
@@ -101,65 +115,100 @@ impl Request<Listing> for ListingRequest {
         }
 
         Ok(client.http_client
-            .get(format!("{}r/{}/{}", REDDIT_API_URL, self.subreddit, listing_string))
-            .bearer_auth(
-                client.oauth_client.access_token.clone().ok_or(
-                    Error::InternalError("No access token found in oauth_client.".to_string())
-                    )?.secret())
+            .get(format!("{}{}/{}", REDDIT_API_URL, self.path, listing_string))
+            .bearer_auth(client.access_token()?)
             .query(&query_params))
     }
 
-    fn construct(&self, client: &RedditClient) -> Result<HTTPRequest, Error> {
-        self.get_filled_builder(client)?
-            .build()
-            .map_err(|e| { Error::InternalError(format!("Failed to build Request from builder: {:?}", e)) }) 
-    }
-
     async fn send(&self, client: &RedditClient) -> Result<Listing, Error> {
-        let res = self.get_filled_builder(client)?
-            .send()
-            .await
-            .map_err(|e| { Error::RequestError(format!("Error occurred while sending request: {:?}", e)) })?;
-
-        // TODO: Better error handling.
-        let v: Value = serde_json::from_str(&res.text().await.map_err(
-                |e| { Error::InternalError(e.to_string()) })?).map_err(
-            |e| { Error::InternalError(e.to_string()) }
-        )?;
-
-        let listing = Listing {
-            after: extract_string_with_default!(v["data"]["after"], "data.after"),
-            before: extract_string_with_default!(v["data"]["before"], "data.before"),
-            posts: Result::<Vec<Post>, Error>::from_iter::<Vec<Result<Post, Error>>>(
-                v["data"]["children"].as_array()
-                .ok_or(Error::InternalError("Can't convert to vector".to_string()))?
-                .into_iter()
-                .map(|v_post| -> Result<Post, Error> { 
-                    Ok(Post { 
-                        subreddit: extract_string_with_error!(v_post["data"]["subreddit"], "data.subreddit"),
-                        title: extract_string_with_error!(v_post["data"]["title"], "data.title"),
-                        selftext: extract_string_with_error!(v_post["data"]["selftext"], "data.selftext"),
-                        score: v_post["data"]["score"].as_u64()
-                            .ok_or(Error::InternalError(
-                                    format!("Failed to parse to u64: {}", "data.score")))?
-                    }) 
-                })
-                .collect())?
-        };
-
-        // println!("{}", res.text().await.map_err(|e| { Error::Failure(e.to_string()) })?);
-        println!("{:?}", listing);
-
-        Ok(listing)
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        Listing::from_value(&v)
     }
 }
 
-#[derive(Default, Debug)]
+#[derive(Clone, Default, Debug)]
 pub struct Post {
+    pub id: String,
+    pub name: String,
+    pub author: String,
     pub subreddit: String,
     pub title: String,
     pub selftext: String,
-    pub score: u64
+    pub url: String,
+    pub permalink: String,
+    pub score: i64,
+    pub num_comments: u64,
+    pub created_utc: f64,
+    pub over_18: bool,
+    // The user's vote: true for up, false for down and None if they have not voted.
+    pub likes: Option<bool>,
+    pub poll: Option<Poll>
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct PollOption {
+    pub id: String,
+    pub text: String,
+    // Only visible once the user has voted or voting has ended.
+    pub vote_count: Option<u64>
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct Poll {
+    pub options: Vec<PollOption>,
+    pub total_vote_count: u64,
+    // Milliseconds since the Unix epoch.
+    pub voting_end_timestamp: u64,
+    // ID of the option the user voted for.
+    pub user_selection: Option<String>
+}
+
+impl Poll {
+    // Parses the `poll_data` object of a post.
+    pub(crate) fn from_data(data: &Value) -> Result<Self, Error> {
+        Ok(Poll {
+            options: data["options"].as_array()
+                .ok_or(Error::InternalError("Can't convert to vector".to_string()))?
+                .iter()
+                .map(|option| -> Result<PollOption, Error> {
+                    Ok(PollOption {
+                        id: extract_string_with_error!(option["id"], "poll_data.options.id"),
+                        text: extract_string_with_error!(option["text"], "poll_data.options.text"),
+                        vote_count: option["vote_count"].as_u64()
+                    })
+                })
+                .collect::<Result<Vec<PollOption>, Error>>()?,
+            total_vote_count: data["total_vote_count"].as_u64().unwrap_or_default(),
+            voting_end_timestamp: data["voting_end_timestamp"].as_u64().unwrap_or_default(),
+            user_selection: data["user_selection"].as_str().map(|id| { id.to_string() })
+        })
+    }
+}
+
+impl Post {
+    // Parses the `data` object of a `t3` thing.
+    pub(crate) fn from_data(data: &Value) -> Result<Self, Error> {
+        Ok(Post {
+            id: extract_string_with_error!(data["id"], "data.id"),
+            name: extract_string_with_error!(data["name"], "data.name"),
+            author: extract_string_with_default!(data["author"], "data.author"),
+            subreddit: extract_string_with_error!(data["subreddit"], "data.subreddit"),
+            title: extract_string_with_error!(data["title"], "data.title"),
+            selftext: extract_string_with_error!(data["selftext"], "data.selftext"),
+            url: extract_string_with_default!(data["url"], "data.url"),
+            permalink: extract_string_with_default!(data["permalink"], "data.permalink"),
+            score: data["score"].as_i64()
+                .ok_or(Error::InternalError(format!("Failed to parse to i64: {}", "data.score")))?,
+            num_comments: data["num_comments"].as_u64().unwrap_or_default(),
+            created_utc: data["created_utc"].as_f64().unwrap_or_default(),
+            over_18: data["over_18"].as_bool().unwrap_or_default(),
+            likes: data["likes"].as_bool(),
+            poll: match data["poll_data"].is_object() {
+                true => Some(Poll::from_data(&data["poll_data"])?),
+                false => None
+            }
+        })
+    }
 }
 
 #[derive(Default, Debug)]
@@ -169,7 +218,21 @@ pub struct Listing {
     pub posts: Vec<Post>
 }
 
-struct ListingRequestBuilder {
+impl Listing {
+    pub(crate) fn from_value(v: &Value) -> Result<Self, Error> {
+        Ok(Listing {
+            after: extract_string_with_default!(v["data"]["after"], "data.after"),
+            before: extract_string_with_default!(v["data"]["before"], "data.before"),
+            posts: v["data"]["children"].as_array()
+                .ok_or(Error::InternalError("Can't convert to vector".to_string()))?
+                .iter()
+                .map(|v_post| -> Result<Post, Error> { Post::from_data(&v_post["data"]) })
+                .collect::<Result<Vec<Post>, Error>>()?
+        })
+    }
+}
+
+pub struct ListingRequestBuilder {
     req: ListingRequest
 }
 
@@ -183,21 +246,29 @@ macro_rules! assert_listing_type {
 }
 
 impl ListingRequestBuilder {
-    fn new(subreddit: &str, listing_type: ListingType) -> Self {
+    pub fn new(subreddit: &str, listing_type: ListingType) -> Self {
+        ListingRequestBuilder::with_path(format!("r/{}", subreddit), listing_type)
+    }
+
+    // Lists the posts of the multireddit at `multipath`, e.g. `/user/{username}/m/{name}`.
+    pub fn multireddit(multipath: &str, listing_type: ListingType) -> Result<Self, Error> {
+        let multipath = normalize_multipath(multipath)?;
+        Ok(ListingRequestBuilder::with_path(multipath.trim_start_matches('/').to_string(), listing_type))
+    }
+
+    fn with_path(path: String, listing_type: ListingType) -> Self {
         ListingRequestBuilder {
             req: ListingRequest {
-                subreddit: subreddit.to_string(),
+                path,
                 listing_type,
-                after: None,
-                before: None,
-                limit: None,
+                pagination: Pagination::default(),
                 g: None,
                 t: None
             }
         }
     }
 
-    fn after(mut self, after: String) -> Result<Self, Error> {
+    pub fn after(mut self, after: String) -> Result<Self, Error> {
         assert_listing_type!(self.req.listing_type,
                              ListingType::Top,
                              ListingType::New,
@@ -205,11 +276,11 @@ impl ListingRequestBuilder {
                              ListingType::Rising,
                              ListingType::Controversial
                              );
-        self.req.after = Some(after);
+        self.req.pagination.after = Some(after);
         Ok(self)
     }
 
-    fn before(mut self, before: String) -> Result<Self, Error> {
+    pub fn before(mut self, before: String) -> Result<Self, Error> {
         assert_listing_type!(self.req.listing_type,
                              ListingType::Top,
                              ListingType::New,
@@ -217,11 +288,11 @@ impl ListingRequestBuilder {
                              ListingType::Rising,
                              ListingType::Controversial
                              );
-        self.req.before = Some(before);
+        self.req.pagination.before = Some(before);
         Ok(self)
     }
 
-    fn limit(mut self, limit: u32) -> Result<Self, Error> {
+    pub fn limit(mut self, limit: u32) -> Result<Self, Error> {
         assert_listing_type!(self.req.listing_type,
                              ListingType::Top,
                              ListingType::Hot,
@@ -230,11 +301,11 @@ impl ListingRequestBuilder {
                              ListingType::Rising,
                              ListingType::Controversial
                              );
-        self.req.limit = Some(limit);
+        self.req.pagination.limit = Some(limit);
         Ok(self)
     }
 
-    fn g(mut self, g: String) -> Result<Self, Error> {
+    pub fn g(mut self, g: String) -> Result<Self, Error> {
         assert_listing_type!(self.req.listing_type,
                              ListingType::Best
                              );
@@ -242,7 +313,7 @@ impl ListingRequestBuilder {
         Ok(self)
     }
 
-    fn t(mut self, sort_time: SortTime) -> Result<Self, Error> {
+    pub fn t(mut self, sort_time: SortTime) -> Result<Self, Error> {
         assert_listing_type!(self.req.listing_type,
                              ListingType::Top,
                              ListingType::Controversial
@@ -292,4 +363,25 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_parse_poll_data() -> Result<()> {
+        let data = serde_json::json!({
+            "id": "abc", "name": "t3_abc", "subreddit": "rust", "title": "Poll", "selftext": "", "score": 1,
+            "poll_data": {
+                "options": [{ "id": "1", "text": "A", "vote_count": 4 }, { "id": "2", "text": "B", "vote_count": 6 }],
+                "total_vote_count": 10,
+                "voting_end_timestamp": 1666000000000u64,
+                "user_selection": "2"
+            }
+        });
+
+        let poll = Post::from_data(&data)?.poll.ok_or(Error::InternalError("No poll".to_string()))?;
+        assert_eq!(poll.options.iter().map(|o| { o.vote_count }).collect::<Vec<_>>(), vec![Some(4), Some(6)]);
+        assert_eq!(poll.total_vote_count, 10);
+        assert_eq!(poll.voting_end_timestamp, 1666000000000);
+        assert_eq!(poll.user_selection.as_deref(), Some("2"));
+        Ok(())
+    }
+
 }
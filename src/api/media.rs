@@ -0,0 +1,347 @@
+use std::time::{ SystemTime, UNIX_EPOCH };
+
+use reqwest::RequestBuilder as HTTPRequestBuilder;
+use reqwest::header::CONTENT_TYPE;
+use url::Url;
+use async_trait::async_trait;
+use serde_json::Value;
+
+use super::util::{
+    extract_string_with_default,
+    extract_string_with_error,
+    send_for_json,
+    Request,
+    Error
+};
+use super::submit::SubmitKind;
+use crate::REDDIT_API_URL;
+use super::util::RedditClient;
+
+// Permission to upload a single file to Reddit's media storage, returned by
+// /api/media/asset.json.
+#[derive(Clone, Default, Debug)]
+pub struct MediaLease {
+    // Used as `media_id` of gallery items.
+    pub asset_id: String,
+    pub upload_url: String,
+    // Form fields that must be sent along with the file, in order.
+    pub fields: Vec<(String, String)>,
+    pub websocket_url: String,
+    pub filename: String,
+    pub mimetype: String
+}
+
+impl MediaLease {
+    pub(crate) fn from_value(v: &Value, filename: &str, mimetype: &str) -> Result<Self, Error> {
+        let action = extract_string_with_error!(v["args"]["action"], "args.action");
+        Ok(MediaLease {
+            asset_id: extract_string_with_error!(v["asset"]["asset_id"], "asset.asset_id"),
+            // The storage URL comes without a scheme.
+            upload_url: match action.starts_with("//") {
+                true => format!("https:{}", action),
+                false => action
+            },
+            fields: v["args"]["fields"].as_array()
+                .ok_or(Error::InternalError("Can't convert to vector".to_string()))?
+                .iter()
+                .map(|field| -> Result<(String, String), Error> {
+                    Ok((
+                        extract_string_with_error!(field["name"], "args.fields.name"),
+                        extract_string_with_error!(field["value"], "args.fields.value")
+                    ))
+                })
+                .collect::<Result<Vec<(String, String)>, Error>>()?,
+            websocket_url: extract_string_with_default!(v["asset"]["websocket_url"], "asset.websocket_url"),
+            filename: filename.to_string(),
+            mimetype: mimetype.to_string()
+        })
+    }
+
+    fn field(&self, name: &str) -> Option<&str> {
+        self.fields.iter().find(|(key, _)| { key == name }).map(|(_, value)| { value.as_str() })
+    }
+}
+
+#[derive(Clone)]
+pub struct MediaLeaseRequest {
+    filename: String,
+    mimetype: String
+}
+
+impl MediaLeaseRequest {
+    // `mimetype` must be an image or video type Reddit accepts, e.g. `image/png` or `video/mp4`.
+    // `filename` is sent as is in the upload's multipart header, so it can't contain quotes,
+    // backslashes or control characters.
+    pub fn new(filename: &str, mimetype: &str) -> Result<Self, Error> {
+        if filename.is_empty() || filename.chars().any(|c| { c == '"' || c == '\\' || c.is_control() }) {
+            return Err(Error::UserError(format!("Invalid media filename: {:?}", filename)));
+        }
+        if !mimetype.starts_with("image/") && !mimetype.starts_with("video/") {
+            return Err(Error::UserError(format!("Not an image or video mimetype: {}", mimetype)));
+        }
+        Ok(MediaLeaseRequest {
+            filename: filename.to_string(),
+            mimetype: mimetype.to_string()
+        })
+    }
+}
+
+#[async_trait]
+impl Request<MediaLease> for MediaLeaseRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        Ok(client.http_client
+            .post(format!("{}api/media/asset.json", REDDIT_API_URL))
+            .bearer_auth(client.access_token()?)
+            .form(&[("filepath", &self.filename), ("mimetype", &self.mimetype)]))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<MediaLease, Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        MediaLease::from_value(&v, &self.filename, &self.mimetype)
+    }
+}
+
+// Media that has been uploaded and can be submitted.
+#[derive(Clone, Default, Debug)]
+pub struct UploadedMedia {
+    pub asset_id: String,
+    // Used as the URL of image and video submissions.
+    pub url: String
+}
+
+impl UploadedMedia {
+    // Submits this media as an image post.
+    pub fn image(&self) -> SubmitKind {
+        SubmitKind::Image(self.url.clone())
+    }
+
+    // Submits this media as a video post, with the uploaded image `poster` shown before playback.
+    pub fn video(&self, poster: &UploadedMedia) -> SubmitKind {
+        SubmitKind::Video { url: self.url.clone(), poster_url: poster.url.clone() }
+    }
+
+    // Adds this image to a gallery post.
+    pub fn gallery_item(&self) -> GalleryItem {
+        GalleryItem::new(&self.asset_id)
+    }
+}
+
+// Uploads `data` to the storage URL of `lease`. This request goes to the storage server rather than
+// to Reddit, so it does not carry the access token.
+pub async fn upload_media(client: &RedditClient, lease: &MediaLease, data: Vec<u8>) -> Result<UploadedMedia, Error> {
+    let boundary = format!(
+        "ravana-{}",
+        SystemTime::now().duration_since(UNIX_EPOCH).map(|d| { d.as_nanos() }).unwrap_or_default()
+    );
+
+    let res = client.http_client
+        .post(&lease.upload_url)
+        .header(CONTENT_TYPE, format!("multipart/form-data; boundary={}", boundary))
+        .body(multipart_body(&boundary, lease, data))
+        .send()
+        .await
+        .map_err(|e| { Error::RequestError(format!("Error occurred while uploading media: {:?}", e)) })?;
+
+    let status = res.status();
+    let text = res.text().await.map_err(|e| { Error::RequestError(e.to_string()) })?;
+    if !status.is_success() {
+        return Err(Error::RequestError(format!("Upload server returned status {}: {}", status, text)));
+    }
+
+    // The storage server answers with an XML document holding the uploaded file's location.
+    let url = match (text.find("<Location>"), text.find("</Location>")) {
+        (Some(start), Some(end)) if start < end => text[start + "<Location>".len()..end].replace("&amp;", "&"),
+        _ => {
+            let key = lease.field("key")
+                .ok_or(Error::InternalError("No key field in media lease.".to_string()))?;
+            key_url(&lease.upload_url, key)?
+        }
+    };
+
+    Ok(UploadedMedia {
+        asset_id: lease.asset_id.clone(),
+        url
+    })
+}
+
+// URL of the object stored under `key`, encoded like the `<Location>` the server returns.
+fn key_url(upload_url: &str, key: &str) -> Result<String, Error> {
+    let mut url = Url::parse(upload_url)
+        .map_err(|e| { Error::InternalError(format!("Invalid upload URL {}: {}", upload_url, e)) })?;
+    url.path_segments_mut()
+        .map_err(|_| { Error::InternalError(format!("Invalid upload URL: {}", upload_url)) })?
+        .pop_if_empty()
+        .push(key);
+    Ok(url.to_string())
+}
+
+// Requests an upload lease for `filename` and uploads `data` with it. The result is submitted with
+// `image`, `video` or `gallery_item` and a `SubmitRequestBuilder`.
+pub async fn upload(client: &RedditClient,
+                    filename: &str,
+                    mimetype: &str,
+                    data: Vec<u8>) -> Result<UploadedMedia, Error> {
+    let lease = MediaLeaseRequest::new(filename, mimetype)?.send(client).await?;
+    upload_media(client, &lease, data).await
+}
+
+fn multipart_body(boundary: &str, lease: &MediaLease, data: Vec<u8>) -> Vec<u8> {
+    let mut body: Vec<u8> = Vec::new();
+    for (name, value) in lease.fields.iter() {
+        body.extend(format!(
+                "--{}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n",
+                boundary, name, value).as_bytes());
+    }
+    body.extend(format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+            boundary, lease.filename, lease.mimetype).as_bytes());
+    body.extend(data);
+    body.extend(format!("\r\n--{}--\r\n", boundary).as_bytes());
+    body
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct GalleryItem {
+    pub media_id: String,
+    pub caption: String,
+    pub outbound_url: String
+}
+
+impl GalleryItem {
+    // `media_id` is the asset ID of an uploaded image.
+    pub fn new(media_id: &str) -> Self {
+        GalleryItem {
+            media_id: media_id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn caption(mut self, caption: &str) -> Self {
+        self.caption = caption.to_string();
+        self
+    }
+
+    pub fn outbound_url(mut self, outbound_url: &str) -> Self {
+        self.outbound_url = outbound_url.to_string();
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use serde_json::json;
+    use tokio::io::{ AsyncReadExt, AsyncWriteExt };
+    use tokio::net::TcpListener;
+    use super::*;
+    use crate::api::submit::SubmitRequestBuilder;
+    use crate::api::util::{ test_body, test_client, RequestBuilder };
+
+    #[test]
+    fn test_media_lease() -> Result<()> {
+        let req = MediaLeaseRequest::new("cat.png", "image/png")?.construct(&test_client())?;
+        assert_eq!(req.url().path(), "/api/media/asset.json");
+        assert_eq!(test_body(&req), "filepath=cat.png&mimetype=image%2Fpng");
+        assert!(MediaLeaseRequest::new("cat.txt", "text/plain").is_err());
+        assert!(MediaLeaseRequest::new("", "image/png").is_err());
+        assert!(MediaLeaseRequest::new("cat\".png", "image/png").is_err());
+        assert!(MediaLeaseRequest::new("cat\r\nX-Evil: 1.png", "image/png").is_err());
+        assert!(MediaLeaseRequest::new("cat\\.png", "image/png").is_err());
+        assert!(MediaLeaseRequest::new("my cat.png", "image/png").is_ok());
+
+        let v = json!({
+            "args": {
+                "action": "//reddit-uploaded-media.s3-accelerate.amazonaws.com",
+                "fields": [{ "name": "key", "value": "rte_images/abc" }, { "name": "acl", "value": "private" }]
+            },
+            "asset": { "asset_id": "abc", "websocket_url": "wss://ws" }
+        });
+        let lease = MediaLease::from_value(&v, "cat.png", "image/png")?;
+        assert_eq!(lease.upload_url, "https://reddit-uploaded-media.s3-accelerate.amazonaws.com");
+        assert_eq!(lease.field("acl"), Some("private"));
+        Ok(())
+    }
+
+    // Stands in for the storage server: accepts one upload and answers like S3 does.
+    async fn serve_upload(listener: TcpListener) -> Result<String> {
+        let (mut stream, _) = listener.accept().await?;
+        let mut request: Vec<u8> = Vec::new();
+        let mut buf = [0u8; 4096];
+        loop {
+            let n = stream.read(&mut buf).await?;
+            request.extend(&buf[..n]);
+            let text = String::from_utf8_lossy(&request).to_string();
+            if let Some(header_end) = text.find("\r\n\r\n") {
+                let content_length: usize = text[..header_end].lines()
+                    .find_map(|line| { line.to_lowercase().strip_prefix("content-length: ").map(|v| { v.to_string() }) })
+                    .unwrap_or_default()
+                    .parse()?;
+                if request.len() >= header_end + 4 + content_length || n == 0 {
+                    break;
+                }
+            }
+        }
+
+        let body = "<PostResponse><Location>http://storage/rte_images%2Fabc</Location></PostResponse>";
+        stream.write_all(format!("HTTP/1.1 201 Created\r\ncontent-length: {}\r\n\r\n{}", body.len(), body).as_bytes()).await?;
+        Ok(String::from_utf8_lossy(&request).to_string())
+    }
+
+    #[tokio::test]
+    async fn test_upload_media() -> Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let lease = MediaLease {
+            asset_id: "abc".to_string(),
+            upload_url: format!("http://{}", listener.local_addr()?),
+            fields: vec![("key".to_string(), "rte_images/abc".to_string())],
+            filename: "cat.png".to_string(),
+            mimetype: "image/png".to_string(),
+            ..Default::default()
+        };
+        let server = tokio::spawn(serve_upload(listener));
+
+        let uploaded = upload_media(&test_client(), &lease, b"PNGDATA".to_vec()).await?;
+        let request = server.await??;
+
+        assert_eq!(uploaded.asset_id, "abc");
+        assert_eq!(uploaded.url, "http://storage/rte_images%2Fabc");
+        assert!(!request.contains("Bearer"));
+        assert!(request.contains("name=\"key\"\r\n\r\nrte_images/abc\r\n"));
+        assert!(request.contains("name=\"file\"; filename=\"cat.png\"\r\nContent-Type: image/png\r\n\r\nPNGDATA\r\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_key_url() -> Result<()> {
+        assert_eq!(
+            key_url("https://reddit-uploaded-media.s3-accelerate.amazonaws.com", "rte_images/a b.png")?,
+            "https://reddit-uploaded-media.s3-accelerate.amazonaws.com/rte_images%2Fa%20b.png"
+        );
+        assert_eq!(key_url("http://storage/", "abc")?, "http://storage/abc");
+        assert!(key_url("not a url", "abc").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_submit_uploaded_media() -> Result<()> {
+        let image = UploadedMedia {
+            asset_id: "abc".to_string(),
+            url: "https://storage/rte_images%2Fabc".to_string()
+        };
+        let video = UploadedMedia {
+            asset_id: "def".to_string(),
+            url: "https://storage/rte_videos%2Fdef".to_string()
+        };
+
+        let req = SubmitRequestBuilder::new("test", "Cat", image.image())?.build().construct(&test_client())?;
+        assert!(test_body(&req).ends_with("&kind=image&url=https%3A%2F%2Fstorage%2Frte_images%252Fabc"));
+
+        let req = SubmitRequestBuilder::new("test", "Cat", video.video(&image))?.build().construct(&test_client())?;
+        assert!(test_body(&req).ends_with(
+                "&kind=video&url=https%3A%2F%2Fstorage%2Frte_videos%252Fdef\
+                &video_poster_url=https%3A%2F%2Fstorage%2Frte_images%252Fabc"));
+
+        assert_eq!(image.gallery_item().media_id, "abc");
+        Ok(())
+    }
+}
@@ -0,0 +1,707 @@
+use std::collections::{ HashSet, VecDeque };
+use std::pin::Pin;
+use std::task::{ Context, Poll };
+use std::time::Duration;
+use reqwest::RequestBuilder as HTTPRequestBuilder;
+use async_trait::async_trait;
+use futures_core::Stream;
+use futures_util::stream;
+use serde_json::Value;
+
+use super::util::{
+    check_api_errors,
+    extract_string_with_default,
+    extract_string_with_error,
+    send_for_json,
+    Request,
+    RequestBuilder,
+    Error
+};
+use super::listing::Pagination;
+use crate::REDDIT_API_URL;
+use super::util::RedditClient;
+
+// A private message, or a comment reply or username mention delivered to the inbox. The latter
+// are `t1` things with `was_comment` set.
+#[derive(Clone, Default, Debug)]
+pub struct Message {
+    pub id: String,
+    pub name: String,
+    pub author: String,
+    // Recipient, a username or `#subreddit` for modmail.
+    pub dest: String,
+    pub subject: String,
+    pub body: String,
+    pub body_html: String,
+    pub subreddit: String,
+    pub parent_id: String,
+    // Fullname of the first message in the conversation.
+    pub first_message_name: String,
+    // Permalink of the comment, for comment replies and mentions.
+    pub context: String,
+    pub link_title: String,
+    pub was_comment: bool,
+    // Whether the message is unread.
+    pub new: bool,
+    pub created_utc: f64
+}
+
+impl Message {
+    // Parses the `data` object of a `t4` thing, or of a `t1` thing from an inbox listing.
+    pub(crate) fn from_data(data: &Value) -> Result<Self, Error> {
+        Ok(Message {
+            id: extract_string_with_error!(data["id"], "data.id"),
+            name: extract_string_with_error!(data["name"], "data.name"),
+            author: extract_string_with_default!(data["author"], "data.author"),
+            dest: extract_string_with_default!(data["dest"], "data.dest"),
+            subject: extract_string_with_default!(data["subject"], "data.subject"),
+            body: extract_string_with_default!(data["body"], "data.body"),
+            body_html: extract_string_with_default!(data["body_html"], "data.body_html"),
+            subreddit: extract_string_with_default!(data["subreddit"], "data.subreddit"),
+            parent_id: extract_string_with_default!(data["parent_id"], "data.parent_id"),
+            first_message_name: extract_string_with_default!(data["first_message_name"], "data.first_message_name"),
+            context: extract_string_with_default!(data["context"], "data.context"),
+            link_title: extract_string_with_default!(data["link_title"], "data.link_title"),
+            was_comment: data["was_comment"].as_bool().unwrap_or_default(),
+            new: data["new"].as_bool().unwrap_or_default(),
+            created_utc: data["created_utc"].as_f64().unwrap_or_default()
+        })
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct MessageListing {
+    pub after: String,
+    pub before: String,
+    pub messages: Vec<Message>
+}
+
+impl MessageListing {
+    pub(crate) fn from_value(v: &Value) -> Result<Self, Error> {
+        Ok(MessageListing {
+            after: extract_string_with_default!(v["data"]["after"], "data.after"),
+            before: extract_string_with_default!(v["data"]["before"], "data.before"),
+            messages: v["data"]["children"].as_array()
+                .ok_or(Error::InternalError("Can't convert to vector".to_string()))?
+                .iter()
+                .map(|v_message| -> Result<Message, Error> { Message::from_data(&v_message["data"]) })
+                .collect::<Result<Vec<Message>, Error>>()?
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum MessageWhere {
+    // Everything delivered to the user.
+    Inbox,
+    Unread,
+    Sent,
+    // Private messages only.
+    Messages,
+    // Replies to the user's comments.
+    CommentReplies,
+    // Replies to the user's posts.
+    SelfReply,
+    // Comments mentioning the user's name.
+    Mentions
+}
+
+impl MessageWhere {
+    fn as_str(&self) -> &'static str {
+        match self {
+            MessageWhere::Inbox => "inbox",
+            MessageWhere::Unread => "unread",
+            MessageWhere::Sent => "sent",
+            MessageWhere::Messages => "messages",
+            MessageWhere::CommentReplies => "comments",
+            MessageWhere::SelfReply => "selfreply",
+            MessageWhere::Mentions => "mentions"
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct MessageListingRequest {
+    where_type: MessageWhere,
+    mark: bool,
+    pagination: Pagination
+}
+
+#[async_trait]
+impl Request<MessageListing> for MessageListingRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        let mut query_params: Vec<(&str, String)> = vec![("mark", self.mark.to_string())];
+
+        self.pagination.push_query_params(&mut query_params);
+
+        Ok(client.http_client
+            .get(format!("{}message/{}", REDDIT_API_URL, self.where_type.as_str()))
+            .bearer_auth(client.access_token()?)
+            .query(&query_params))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<MessageListing, Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        MessageListing::from_value(&v)
+    }
+}
+
+pub struct MessageListingRequestBuilder {
+    req: MessageListingRequest
+}
+
+impl MessageListingRequestBuilder {
+    pub fn new(where_type: MessageWhere) -> Self {
+        MessageListingRequestBuilder {
+            req: MessageListingRequest {
+                where_type,
+                mark: false,
+                pagination: Pagination::default()
+            }
+        }
+    }
+
+    // Mark the listed messages as read. Defaults to false.
+    pub fn mark(mut self, mark: bool) -> Self {
+        self.req.mark = mark;
+        self
+    }
+
+    pub fn after(mut self, after: String) -> Self {
+        self.req.pagination.after = Some(after);
+        self
+    }
+
+    pub fn before(mut self, before: String) -> Self {
+        self.req.pagination.before = Some(before);
+        self
+    }
+
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.req.pagination.limit = Some(limit);
+        self
+    }
+
+    pub fn count(mut self, count: u32) -> Self {
+        self.req.pagination.count = Some(count);
+        self
+    }
+}
+
+impl RequestBuilder<MessageListingRequest> for MessageListingRequestBuilder {
+    fn build(&self) -> MessageListingRequest {
+        self.req.clone()
+    }
+}
+
+const MAX_SUBJECT_LENGTH: usize = 100;
+
+fn assert_message(id: &str) -> Result<(), Error> {
+    match id.starts_with("t4_") {
+        true => Ok(()),
+        false => Err(Error::UserError(format!("Not a message fullname: {}", id)))
+    }
+}
+
+#[derive(Clone)]
+pub struct ComposeRequest {
+    to: String,
+    subject: String,
+    text: String,
+    from_sr: Option<String>
+}
+
+#[async_trait]
+impl Request<()> for ComposeRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        let mut form_params: Vec<(&str, String)> = vec![
+            ("api_type", "json".to_string()),
+            ("to", self.to.clone()),
+            ("subject", self.subject.clone()),
+            ("text", self.text.clone())
+        ];
+
+        if let Some(from_sr) = &self.from_sr {
+            form_params.push(("from_sr", from_sr.clone()));
+        }
+
+        Ok(client.http_client
+            .post(format!("{}api/compose", REDDIT_API_URL))
+            .bearer_auth(client.access_token()?)
+            .form(&form_params))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<(), Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        check_api_errors(&v)
+    }
+}
+
+pub struct ComposeRequestBuilder {
+    req: ComposeRequest
+}
+
+impl ComposeRequestBuilder {
+    // Messages the user `to`, or the moderators of a subreddit when `to` is `/r/{subreddit}`.
+    pub fn new(to: &str, subject: &str, text: &str) -> Result<Self, Error> {
+        if subject.is_empty() || subject.chars().count() > MAX_SUBJECT_LENGTH {
+            return Err(Error::UserError(format!(
+                        "Subject must be between 1 and {} characters long: {:?}",
+                        MAX_SUBJECT_LENGTH,
+                        subject)));
+        }
+        Ok(ComposeRequestBuilder {
+            req: ComposeRequest {
+                to: to.to_string(),
+                subject: subject.to_string(),
+                text: text.to_string(),
+                from_sr: None
+            }
+        })
+    }
+
+    // Send as the moderators of `subreddit` rather than as the user.
+    pub fn from_subreddit(mut self, subreddit: &str) -> Self {
+        self.req.from_sr = Some(subreddit.to_string());
+        self
+    }
+}
+
+impl RequestBuilder<ComposeRequest> for ComposeRequestBuilder {
+    fn build(&self) -> ComposeRequest {
+        self.req.clone()
+    }
+}
+
+#[derive(Clone)]
+pub struct MessageReplyRequest {
+    thing_id: String,
+    text: String
+}
+
+impl MessageReplyRequest {
+    // Replies with markdown `text` to the private message with fullname `thing_id`. Comment
+    // replies from the inbox are answered with usertext::ReplyRequest.
+    pub fn new(thing_id: &str, text: &str) -> Result<Self, Error> {
+        assert_message(thing_id)?;
+        Ok(MessageReplyRequest {
+            thing_id: thing_id.to_string(),
+            text: text.to_string()
+        })
+    }
+}
+
+#[async_trait]
+impl Request<Message> for MessageReplyRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        Ok(client.http_client
+            .post(format!("{}api/comment", REDDIT_API_URL))
+            .bearer_auth(client.access_token()?)
+            .form(&[("api_type", "json"), ("thing_id", &self.thing_id), ("text", &self.text)]))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<Message, Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        check_api_errors(&v)?;
+        Message::from_data(&v["json"]["data"]["things"][0]["data"])
+    }
+}
+
+#[derive(Clone)]
+pub struct MarkMessagesRequest {
+    ids: Vec<String>,
+    unread: bool
+}
+
+impl MarkMessagesRequest {
+    // Marks the inbox items with the given fullnames as read. Comment replies and mentions are
+    // marked by their `t1_...` fullname.
+    pub fn read(ids: Vec<String>) -> Result<Self, Error> {
+        if ids.is_empty() {
+            return Err(Error::UserError("No messages to mark.".to_string()));
+        }
+        Ok(MarkMessagesRequest { ids, unread: false })
+    }
+
+    pub fn unread(ids: Vec<String>) -> Result<Self, Error> {
+        let mut req = MarkMessagesRequest::read(ids)?;
+        req.unread = true;
+        Ok(req)
+    }
+}
+
+#[async_trait]
+impl Request<()> for MarkMessagesRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        let path = match self.unread {
+            true => "api/unread_message",
+            false => "api/read_message"
+        };
+
+        Ok(client.http_client
+            .post(format!("{}{}", REDDIT_API_URL, path))
+            .bearer_auth(client.access_token()?)
+            .form(&[("id", self.ids.join(","))]))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<(), Error> {
+        send_for_json(self.get_filled_builder(client)?).await?;
+        Ok(())
+    }
+}
+
+// Marks everything in the inbox as read. Reddit processes this asynchronously.
+#[derive(Clone)]
+pub struct ReadAllMessagesRequest;
+
+#[async_trait]
+impl Request<()> for ReadAllMessagesRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        Ok(client.http_client
+            .post(format!("{}api/read_all_messages", REDDIT_API_URL))
+            .bearer_auth(client.access_token()?))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<(), Error> {
+        send_for_json(self.get_filled_builder(client)?).await?;
+        Ok(())
+    }
+}
+
+// Removes a private message from the user's inbox.
+#[derive(Clone)]
+pub struct DeleteMessageRequest {
+    id: String
+}
+
+impl DeleteMessageRequest {
+    pub fn new(id: &str) -> Result<Self, Error> {
+        assert_message(id)?;
+        Ok(DeleteMessageRequest { id: id.to_string() })
+    }
+}
+
+#[async_trait]
+impl Request<()> for DeleteMessageRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        Ok(client.http_client
+            .post(format!("{}api/del_msg", REDDIT_API_URL))
+            .bearer_auth(client.access_token()?)
+            .form(&[("id", &self.id)]))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<(), Error> {
+        send_for_json(self.get_filled_builder(client)?).await?;
+        Ok(())
+    }
+}
+
+// Blocks the author of an inbox item, given the item's fullname.
+#[derive(Clone)]
+pub struct BlockAuthorRequest {
+    id: String
+}
+
+impl BlockAuthorRequest {
+    // `id` is a private message (`t4_...`), or a comment reply or mention (`t1_...`).
+    pub fn new(id: &str) -> Result<Self, Error> {
+        if !id.starts_with("t1_") && !id.starts_with("t4_") {
+            return Err(Error::UserError(format!("Not an inbox item fullname: {}", id)));
+        }
+        Ok(BlockAuthorRequest { id: id.to_string() })
+    }
+}
+
+#[async_trait]
+impl Request<()> for BlockAuthorRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        Ok(client.http_client
+            .post(format!("{}api/block", REDDIT_API_URL))
+            .bearer_auth(client.access_token()?)
+            .form(&[("id", &self.id)]))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<(), Error> {
+        send_for_json(self.get_filled_builder(client)?).await?;
+        Ok(())
+    }
+}
+
+const INBOX_POLL_LIMIT: u32 = 100;
+const INBOX_SEEN_CAPACITY: usize = 1000;
+
+// Remembers the fullnames of the most recently yielded inbox items, since items stay in the
+// unread listing until they are marked read. The oldest names are forgotten once `capacity` is
+// reached.
+struct SeenNames {
+    capacity: usize,
+    names: HashSet<String>,
+    order: VecDeque<String>
+}
+
+impl SeenNames {
+    fn new(capacity: usize) -> Self {
+        SeenNames {
+            capacity,
+            names: HashSet::new(),
+            order: VecDeque::new()
+        }
+    }
+
+    // Returns true if `name` had not been seen before.
+    fn insert(&mut self, name: &str) -> bool {
+        if !self.names.insert(name.to_string()) {
+            return false;
+        }
+        self.order.push_back(name.to_string());
+        if self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.names.remove(&oldest);
+            }
+        }
+        true
+    }
+}
+
+// A new inbox item yielded by InboxStream.
+#[derive(Debug)]
+pub struct InboxItem {
+    pub message: Message,
+    mark_read_on_ack: bool
+}
+
+impl InboxItem {
+    // Acknowledges that the message was handled, marking it read if the stream was built with
+    // `mark_read_on_ack`.
+    pub async fn ack(&self, client: &RedditClient) -> Result<(), Error> {
+        match self.mark_read_on_ack {
+            true => MarkMessagesRequest::read(vec![self.message.name.clone()])?.send(client).await,
+            false => Ok(())
+        }
+    }
+}
+
+struct InboxPoller {
+    interval: Duration,
+    mark_read_on_ack: bool,
+    seen: SeenNames,
+    pending: VecDeque<Message>,
+    // Whether to wait before the next poll, after a poll found nothing new or failed.
+    wait: bool
+}
+
+impl InboxPoller {
+    async fn next(&mut self, client: &RedditClient) -> Result<InboxItem, Error> {
+        loop {
+            if let Some(message) = self.pending.pop_front() {
+                return Ok(InboxItem { message, mark_read_on_ack: self.mark_read_on_ack });
+            }
+
+            if self.wait {
+                tokio::time::sleep(self.interval).await;
+            }
+            self.wait = true;
+
+            let listing = MessageListingRequestBuilder::new(MessageWhere::Unread)
+                .limit(INBOX_POLL_LIMIT)
+                .build()
+                .send(client)
+                .await?;
+            self.queue_unseen(listing);
+            self.wait = self.pending.is_empty();
+        }
+    }
+
+    fn queue_unseen(&mut self, listing: MessageListing) {
+        // Listings are newest first.
+        for message in listing.messages.into_iter().rev() {
+            if self.seen.insert(&message.name) {
+                self.pending.push_back(message);
+            }
+        }
+    }
+}
+
+// Polls the unread inbox and yields each new message, comment reply and mention once, oldest
+// first. The stream never ends; a failed poll yields the error and is retried after the interval.
+pub struct InboxStream<'a> {
+    inner: Pin<Box<dyn Stream<Item = Result<InboxItem, Error>> + Send + 'a>>
+}
+
+impl Stream for InboxStream<'_> {
+    type Item = Result<InboxItem, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+pub struct InboxStreamBuilder {
+    interval: Duration,
+    mark_read_on_ack: bool
+}
+
+impl InboxStreamBuilder {
+    pub fn new() -> Self {
+        InboxStreamBuilder {
+            interval: Duration::from_secs(30),
+            mark_read_on_ack: false
+        }
+    }
+
+    // Time to wait between polls when there is nothing new. Defaults to 30 seconds.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    // Mark messages as read when they are acknowledged with InboxItem::ack. Defaults to false.
+    pub fn mark_read_on_ack(mut self, mark_read_on_ack: bool) -> Self {
+        self.mark_read_on_ack = mark_read_on_ack;
+        self
+    }
+
+    pub fn build<'a>(&self, client: &'a RedditClient) -> InboxStream<'a> {
+        let poller = InboxPoller {
+            interval: self.interval,
+            mark_read_on_ack: self.mark_read_on_ack,
+            seen: SeenNames::new(INBOX_SEEN_CAPACITY),
+            pending: VecDeque::new(),
+            wait: false
+        };
+
+        InboxStream {
+            inner: Box::pin(stream::unfold(poller, move |mut poller| async move {
+                let item = poller.next(client).await;
+                Some((item, poller))
+            }))
+        }
+    }
+}
+
+impl Default for InboxStreamBuilder {
+    fn default() -> Self {
+        InboxStreamBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use serde_json::json;
+    use super::*;
+    use crate::api::util::{ test_body, test_client };
+
+    #[test]
+    fn test_message_listing_request() -> Result<()> {
+        let req = MessageListingRequestBuilder::new(MessageWhere::CommentReplies)
+            .limit(10)
+            .build()
+            .construct(&test_client())?;
+        assert_eq!(req.url().path(), "/message/comments");
+        assert_eq!(req.url().query(), Some("mark=false&limit=10"));
+
+        let req = MessageListingRequestBuilder::new(MessageWhere::Unread).mark(true).build().construct(&test_client())?;
+        assert_eq!(req.url().path(), "/message/unread");
+        assert_eq!(req.url().query(), Some("mark=true"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_messages() -> Result<()> {
+        let v = json!({ "kind": "Listing", "data": { "after": "t4_b", "children": [
+            { "kind": "t1", "data": {
+                "id": "a", "name": "t1_a", "author": "bob", "dest": "me", "subject": "comment reply",
+                "body": "Agreed", "was_comment": true, "new": true, "context": "/r/rust/comments/x/y/a/?context=3",
+                "parent_id": "t1_z", "link_title": "Post"
+            } },
+            { "kind": "t4", "data": {
+                "id": "b", "name": "t4_b", "author": "alice", "dest": "me", "subject": "Hi",
+                "body": "Hello", "was_comment": false, "new": false, "first_message_name": null
+            } }
+        ] } });
+
+        let listing = MessageListing::from_value(&v)?;
+        assert_eq!(listing.after, "t4_b");
+        assert!(listing.messages[0].was_comment && listing.messages[0].new);
+        assert_eq!(listing.messages[0].context, "/r/rust/comments/x/y/a/?context=3");
+        assert_eq!(listing.messages[1].subject, "Hi");
+        assert!(listing.messages[1].first_message_name.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_message_actions() -> Result<()> {
+        let client = test_client();
+
+        let req = ComposeRequestBuilder::new("/r/rust", "Question", "Hello mods")?
+            .from_subreddit("learnrust")
+            .build()
+            .construct(&client)?;
+        assert_eq!(req.url().path(), "/api/compose");
+        assert_eq!(test_body(&req), "api_type=json&to=%2Fr%2Frust&subject=Question&text=Hello+mods&from_sr=learnrust");
+        assert!(ComposeRequestBuilder::new("spez", "", "Hi").is_err());
+
+        let req = MessageReplyRequest::new("t4_abc", "Thanks")?.construct(&client)?;
+        assert_eq!(req.url().path(), "/api/comment");
+        assert!(MessageReplyRequest::new("t1_abc", "Thanks").is_err());
+
+        let req = MarkMessagesRequest::read(vec!["t4_a".to_string(), "t1_b".to_string()])?.construct(&client)?;
+        assert_eq!(req.url().path(), "/api/read_message");
+        assert_eq!(test_body(&req), "id=t4_a%2Ct1_b");
+        let req = MarkMessagesRequest::unread(vec!["t4_a".to_string()])?.construct(&client)?;
+        assert_eq!(req.url().path(), "/api/unread_message");
+
+        assert_eq!(ReadAllMessagesRequest.construct(&client)?.url().path(), "/api/read_all_messages");
+        assert_eq!(DeleteMessageRequest::new("t4_a")?.construct(&client)?.url().path(), "/api/del_msg");
+        assert_eq!(test_body(&BlockAuthorRequest::new("t4_a")?.construct(&client)?), "id=t4_a");
+        assert!(BlockAuthorRequest::new("t3_a").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_inbox_stream_dedup() {
+        let listing = |names: &[&str]| -> MessageListing {
+            MessageListing {
+                messages: names.iter()
+                    .map(|name| { Message { name: name.to_string(), ..Message::default() } })
+                    .collect(),
+                ..MessageListing::default()
+            }
+        };
+
+        let mut poller = InboxPoller {
+            interval: Duration::from_secs(30),
+            mark_read_on_ack: false,
+            seen: SeenNames::new(INBOX_SEEN_CAPACITY),
+            pending: VecDeque::new(),
+            wait: false
+        };
+        poller.queue_unseen(listing(&["t4_b", "t1_a"]));
+        // An unacknowledged item stays unread and is polled again.
+        poller.queue_unseen(listing(&["t4_c", "t4_b", "t1_a"]));
+
+        let names: Vec<String> = poller.pending.iter().map(|message| { message.name.clone() }).collect();
+        assert_eq!(names, vec!["t1_a", "t4_b", "t4_c"]);
+    }
+
+    #[test]
+    fn test_seen_names() {
+        let mut seen = SeenNames::new(2);
+        assert!(seen.insert("t4_a"));
+        assert!(!seen.insert("t4_a"));
+        assert!(seen.insert("t4_b"));
+        assert!(seen.insert("t4_c"));
+        // t4_a was forgotten to make room for t4_c.
+        assert!(seen.insert("t4_a"));
+        assert!(!seen.insert("t4_c"));
+    }
+
+    #[tokio::test]
+    async fn test_ack_without_mark_read() -> Result<()> {
+        // Nothing is sent, so the placeholder token is never used.
+        let item = InboxItem { message: Message::default(), mark_read_on_ack: false };
+        item.ack(&test_client()).await?;
+        Ok(())
+    }
+}
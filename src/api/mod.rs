@@ -1,2 +1,19 @@
+pub mod account;
+pub mod comments;
+pub mod flair;
+pub mod info;
 pub mod listing;
+pub mod media;
+pub mod message;
+pub mod multireddit;
+pub mod report;
+pub mod save;
+pub mod search;
+pub mod submit;
+pub mod subreddit;
+pub mod thing;
+pub mod user;
+pub mod usertext;
+pub mod vote;
+pub mod wiki;
 pub mod util;
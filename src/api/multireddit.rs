@@ -0,0 +1,478 @@
+use reqwest::RequestBuilder as HTTPRequestBuilder;
+use async_trait::async_trait;
+use serde_json::{ json, Value };
+
+use super::util::{
+    extract_string_with_default,
+    extract_string_with_error,
+    send_for_json,
+    Request,
+    RequestBuilder,
+    Error
+};
+use crate::REDDIT_API_URL;
+use super::util::RedditClient;
+
+// Checks that `multipath` has the form `/user/{username}/m/{name}` and returns it without a
+// trailing slash.
+pub(crate) fn normalize_multipath(multipath: &str) -> Result<String, Error> {
+    let segments: Vec<&str> = multipath.trim_matches('/').split('/').collect();
+    match segments.as_slice() {
+        ["user", username, "m", name] if !username.is_empty() && !name.is_empty() => {
+            Ok(format!("/user/{}/m/{}", username, name))
+        },
+        _ => Err(Error::UserError(format!("Not a multireddit path: {}", multipath)))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum MultiredditVisibility {
+    Private,
+    Public,
+    // Public, but not listed on the owner's profile.
+    Hidden
+}
+
+impl MultiredditVisibility {
+    fn as_str(&self) -> &'static str {
+        match self {
+            MultiredditVisibility::Private => "private",
+            MultiredditVisibility::Public => "public",
+            MultiredditVisibility::Hidden => "hidden"
+        }
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct Multireddit {
+    pub name: String,
+    pub display_name: String,
+    // Multipath, e.g. `/user/{username}/m/{name}`.
+    pub path: String,
+    pub owner: String,
+    pub description_md: String,
+    // `private`, `public` or `hidden`.
+    pub visibility: String,
+    // Names of the subreddits in the multireddit.
+    pub subreddits: Vec<String>,
+    pub icon_url: String,
+    pub num_subscribers: u64,
+    pub over_18: bool,
+    // Multipath of the multireddit this one was copied from.
+    pub copied_from: Option<String>,
+    pub created_utc: f64
+}
+
+impl Multireddit {
+    // Parses the `data` object of a `LabeledMulti`.
+    pub(crate) fn from_data(data: &Value) -> Result<Self, Error> {
+        Ok(Multireddit {
+            name: extract_string_with_error!(data["name"], "data.name"),
+            display_name: extract_string_with_default!(data["display_name"], "data.display_name"),
+            path: extract_string_with_error!(data["path"], "data.path"),
+            owner: extract_string_with_default!(data["owner"], "data.owner"),
+            description_md: extract_string_with_default!(data["description_md"], "data.description_md"),
+            visibility: extract_string_with_default!(data["visibility"], "data.visibility"),
+            subreddits: data["subreddits"].as_array()
+                .map_or_else(Vec::new, |subreddits| {
+                    subreddits.iter()
+                        .filter_map(|subreddit| { subreddit["name"].as_str().map(|name| { name.to_string() }) })
+                        .collect()
+                }),
+            icon_url: extract_string_with_default!(data["icon_url"], "data.icon_url"),
+            num_subscribers: data["num_subscribers"].as_u64().unwrap_or_default(),
+            over_18: data["over_18"].as_bool().unwrap_or_default(),
+            copied_from: data["copied_from"].as_str().map(|copied_from| { copied_from.to_string() }),
+            created_utc: data["created_utc"].as_f64().unwrap_or_default()
+        })
+    }
+
+    fn from_values(v: &Value) -> Result<Vec<Self>, Error> {
+        v.as_array()
+            .ok_or(Error::InternalError("Can't convert to vector".to_string()))?
+            .iter()
+            .map(|v_multi| -> Result<Multireddit, Error> { Multireddit::from_data(&v_multi["data"]) })
+            .collect()
+    }
+}
+
+#[derive(Clone)]
+pub struct MultiredditsRequest {
+    username: Option<String>
+}
+
+impl MultiredditsRequest {
+    // Multireddits owned by the current user.
+    pub fn mine() -> Self {
+        MultiredditsRequest { username: None }
+    }
+
+    // Public multireddits of `username`.
+    pub fn of_user(username: &str) -> Self {
+        MultiredditsRequest { username: Some(username.to_string()) }
+    }
+}
+
+#[async_trait]
+impl Request<Vec<Multireddit>> for MultiredditsRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        let path = match &self.username {
+            Some(username) => format!("api/multi/user/{}", username),
+            None => "api/multi/mine".to_string()
+        };
+
+        Ok(client.http_client
+            .get(format!("{}{}", REDDIT_API_URL, path))
+            .bearer_auth(client.access_token()?))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<Vec<Multireddit>, Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        Multireddit::from_values(&v)
+    }
+}
+
+#[derive(Clone)]
+pub struct MultiredditRequest {
+    multipath: String
+}
+
+impl MultiredditRequest {
+    pub fn new(multipath: &str) -> Result<Self, Error> {
+        Ok(MultiredditRequest { multipath: normalize_multipath(multipath)? })
+    }
+}
+
+#[async_trait]
+impl Request<Multireddit> for MultiredditRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        Ok(client.http_client
+            .get(format!("{}api/multi{}", REDDIT_API_URL, self.multipath))
+            .bearer_auth(client.access_token()?))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<Multireddit, Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        Multireddit::from_data(&v["data"])
+    }
+}
+
+#[derive(Clone)]
+pub struct MultiredditUpdateRequest {
+    multipath: String,
+    create: bool,
+    display_name: Option<String>,
+    description_md: Option<String>,
+    visibility: Option<MultiredditVisibility>,
+    subreddits: Option<Vec<String>>
+}
+
+impl MultiredditUpdateRequest {
+    fn model(&self) -> Value {
+        let mut model = json!({});
+
+        if let Some(display_name) = &self.display_name {
+            model["display_name"] = json!(display_name);
+        }
+
+        if let Some(description_md) = &self.description_md {
+            model["description_md"] = json!(description_md);
+        }
+
+        if let Some(visibility) = &self.visibility {
+            model["visibility"] = json!(visibility.as_str());
+        }
+
+        if let Some(subreddits) = &self.subreddits {
+            model["subreddits"] = subreddits.iter().map(|name| { json!({ "name": name }) }).collect();
+        }
+
+        model
+    }
+}
+
+#[async_trait]
+impl Request<Multireddit> for MultiredditUpdateRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        let url = format!("{}api/multi{}", REDDIT_API_URL, self.multipath);
+        let builder = match self.create {
+            true => client.http_client.post(url),
+            false => client.http_client.put(url)
+        };
+
+        Ok(builder
+            .bearer_auth(client.access_token()?)
+            .form(&[("multipath", self.multipath.clone()), ("model", self.model().to_string())]))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<Multireddit, Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        Multireddit::from_data(&v["data"])
+    }
+}
+
+pub struct MultiredditUpdateRequestBuilder {
+    req: MultiredditUpdateRequest
+}
+
+impl MultiredditUpdateRequestBuilder {
+    // Creates a multireddit owned by the current user at `multipath`.
+    pub fn create(multipath: &str) -> Result<Self, Error> {
+        Ok(MultiredditUpdateRequestBuilder {
+            req: MultiredditUpdateRequest {
+                multipath: normalize_multipath(multipath)?,
+                create: true,
+                display_name: None,
+                description_md: None,
+                visibility: None,
+                subreddits: None
+            }
+        })
+    }
+
+    // Changes the fields that are set on the existing multireddit at `multipath`.
+    pub fn update(multipath: &str) -> Result<Self, Error> {
+        let mut builder = MultiredditUpdateRequestBuilder::create(multipath)?;
+        builder.req.create = false;
+        Ok(builder)
+    }
+
+    pub fn display_name(mut self, display_name: &str) -> Self {
+        self.req.display_name = Some(display_name.to_string());
+        self
+    }
+
+    pub fn description_md(mut self, description_md: &str) -> Self {
+        self.req.description_md = Some(description_md.to_string());
+        self
+    }
+
+    pub fn visibility(mut self, visibility: MultiredditVisibility) -> Self {
+        self.req.visibility = Some(visibility);
+        self
+    }
+
+    // Replaces the subreddits of the multireddit, given by name.
+    pub fn subreddits(mut self, subreddits: Vec<String>) -> Self {
+        self.req.subreddits = Some(subreddits);
+        self
+    }
+}
+
+impl RequestBuilder<MultiredditUpdateRequest> for MultiredditUpdateRequestBuilder {
+    fn build(&self) -> MultiredditUpdateRequest {
+        self.req.clone()
+    }
+}
+
+#[derive(Clone)]
+pub struct MultiredditCopyRequest {
+    from: String,
+    to: String,
+    rename: bool,
+    display_name: Option<String>
+}
+
+#[async_trait]
+impl Request<Multireddit> for MultiredditCopyRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        let mut form_params: Vec<(&str, String)> = vec![
+            ("from", self.from.clone()),
+            ("to", self.to.clone())
+        ];
+
+        if let Some(display_name) = &self.display_name {
+            form_params.push(("display_name", display_name.clone()));
+        }
+
+        let path = match self.rename {
+            true => "api/multi/rename",
+            false => "api/multi/copy"
+        };
+
+        Ok(client.http_client
+            .post(format!("{}{}", REDDIT_API_URL, path))
+            .bearer_auth(client.access_token()?)
+            .form(&form_params))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<Multireddit, Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        Multireddit::from_data(&v["data"])
+    }
+}
+
+pub struct MultiredditCopyRequestBuilder {
+    req: MultiredditCopyRequest
+}
+
+impl MultiredditCopyRequestBuilder {
+    // Copies the multireddit at `from`, which may be owned by another user, to the current
+    // user's multireddit at `to`.
+    pub fn copy(from: &str, to: &str) -> Result<Self, Error> {
+        Ok(MultiredditCopyRequestBuilder {
+            req: MultiredditCopyRequest {
+                from: normalize_multipath(from)?,
+                to: normalize_multipath(to)?,
+                rename: false,
+                display_name: None
+            }
+        })
+    }
+
+    // Moves the current user's multireddit at `from` to `to`.
+    pub fn rename(from: &str, to: &str) -> Result<Self, Error> {
+        let mut builder = MultiredditCopyRequestBuilder::copy(from, to)?;
+        builder.req.rename = true;
+        Ok(builder)
+    }
+
+    pub fn display_name(mut self, display_name: &str) -> Self {
+        self.req.display_name = Some(display_name.to_string());
+        self
+    }
+}
+
+impl RequestBuilder<MultiredditCopyRequest> for MultiredditCopyRequestBuilder {
+    fn build(&self) -> MultiredditCopyRequest {
+        self.req.clone()
+    }
+}
+
+#[derive(Clone)]
+pub struct MultiredditDeleteRequest {
+    multipath: String
+}
+
+impl MultiredditDeleteRequest {
+    pub fn new(multipath: &str) -> Result<Self, Error> {
+        Ok(MultiredditDeleteRequest { multipath: normalize_multipath(multipath)? })
+    }
+}
+
+#[async_trait]
+impl Request<()> for MultiredditDeleteRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        Ok(client.http_client
+            .delete(format!("{}api/multi{}", REDDIT_API_URL, self.multipath))
+            .bearer_auth(client.access_token()?))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<(), Error> {
+        send_for_json(self.get_filled_builder(client)?).await?;
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+pub struct MultiredditSubredditRequest {
+    multipath: String,
+    subreddit: String,
+    remove: bool
+}
+
+impl MultiredditSubredditRequest {
+    // Adds the subreddit named `subreddit` to the multireddit at `multipath`.
+    pub fn add(multipath: &str, subreddit: &str) -> Result<Self, Error> {
+        Ok(MultiredditSubredditRequest {
+            multipath: normalize_multipath(multipath)?,
+            subreddit: subreddit.to_string(),
+            remove: false
+        })
+    }
+
+    pub fn remove(multipath: &str, subreddit: &str) -> Result<Self, Error> {
+        let mut req = MultiredditSubredditRequest::add(multipath, subreddit)?;
+        req.remove = true;
+        Ok(req)
+    }
+}
+
+#[async_trait]
+impl Request<()> for MultiredditSubredditRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        let url = format!("{}api/multi{}/r/{}", REDDIT_API_URL, self.multipath, self.subreddit);
+
+        Ok(match self.remove {
+            true => client.http_client.delete(url),
+            false => client.http_client
+                .put(url)
+                .form(&[("model", json!({ "name": self.subreddit }).to_string())])
+        }.bearer_auth(client.access_token()?))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<(), Error> {
+        send_for_json(self.get_filled_builder(client)?).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use super::*;
+    use crate::api::listing::{ ListingRequestBuilder, ListingType };
+    use crate::api::util::{ test_body, test_client };
+
+    #[test]
+    fn test_normalize_multipath() -> Result<()> {
+        assert_eq!(normalize_multipath("user/bob/m/cats/")?, "/user/bob/m/cats");
+        assert!(normalize_multipath("/r/cats").is_err());
+        assert!(normalize_multipath("/user//m/cats").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_multireddit_requests() -> Result<()> {
+        let client = test_client();
+
+        let req = MultiredditsRequest::of_user("bob").construct(&client)?;
+        assert_eq!(req.url().path(), "/api/multi/user/bob");
+
+        let req = MultiredditUpdateRequestBuilder::update("/user/bob/m/cats")?
+            .visibility(MultiredditVisibility::Hidden)
+            .subreddits(vec!["cats".to_string()])
+            .build()
+            .construct(&client)?;
+        assert_eq!(req.method(), "PUT");
+        assert_eq!(req.url().path(), "/api/multi/user/bob/m/cats");
+        let model = url::form_urlencoded::parse(test_body(&req).as_bytes())
+            .find(|(key, _)| { key == "model" })
+            .map(|(_, model)| { model.to_string() })
+            .unwrap_or_default();
+        assert_eq!(serde_json::from_str::<Value>(&model)?, json!({ "visibility": "hidden", "subreddits": [{ "name": "cats" }] }));
+
+        let req = MultiredditCopyRequestBuilder::rename("/user/bob/m/cats", "/user/bob/m/kittens")?
+            .build()
+            .construct(&client)?;
+        assert_eq!(req.url().path(), "/api/multi/rename");
+        assert_eq!(test_body(&req), "from=%2Fuser%2Fbob%2Fm%2Fcats&to=%2Fuser%2Fbob%2Fm%2Fkittens");
+
+        let req = MultiredditSubredditRequest::remove("/user/bob/m/cats", "aww")?.construct(&client)?;
+        assert_eq!(req.method(), "DELETE");
+        assert_eq!(req.url().path(), "/api/multi/user/bob/m/cats/r/aww");
+
+        let req = ListingRequestBuilder::multireddit("/user/bob/m/cats/", ListingType::Top)?
+            .limit(5)?
+            .build()
+            .construct(&client)?;
+        assert_eq!(req.url().path(), "/user/bob/m/cats/top");
+        assert_eq!(req.url().query(), Some("limit=5"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_multireddits() -> Result<()> {
+        let v = json!([{ "kind": "LabeledMulti", "data": {
+            "name": "cats", "display_name": "Cats", "path": "/user/bob/m/cats", "owner": "bob",
+            "visibility": "public", "subreddits": [{ "name": "cats" }, { "name": "aww" }],
+            "num_subscribers": 3, "copied_from": null, "created_utc": 1666000000.0
+        } }]);
+
+        let multis = Multireddit::from_values(&v)?;
+        assert_eq!(multis[0].subreddits, vec!["cats", "aww"]);
+        assert_eq!(multis[0].num_subscribers, 3);
+        assert!(multis[0].copied_from.is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,227 @@
+use reqwest::RequestBuilder as HTTPRequestBuilder;
+use async_trait::async_trait;
+
+use super::util::{ check_api_errors, send_for_json, Request, RequestBuilder, Error };
+use super::subreddit::{ SubredditRules, SubredditRulesRequest };
+use crate::REDDIT_API_URL;
+use super::util::RedditClient;
+
+const MAX_REASON_LENGTH: usize = 100;
+const MAX_CUSTOM_TEXT_LENGTH: usize = 250;
+
+#[derive(Clone, Debug)]
+pub enum ReportReason {
+    // Violation reason of one of the subreddit's rules, as listed in ReportReasons::rules.
+    Rule(String),
+    // One of Reddit's site-wide rules, as listed in ReportReasons::site_rules.
+    Site(String),
+    // Free form reason.
+    Other(String)
+}
+
+impl ReportReason {
+    fn text(&self) -> &str {
+        match self {
+            ReportReason::Rule(reason) | ReportReason::Site(reason) | ReportReason::Other(reason) => reason
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ReportRequest {
+    thing_id: String,
+    reason: ReportReason,
+    custom_text: Option<String>,
+    sr_name: Option<String>
+}
+
+#[async_trait]
+impl Request<()> for ReportRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        let mut form_params: Vec<(&str, String)> = vec![
+            ("api_type", "json".to_string()),
+            ("thing_id", self.thing_id.clone())
+        ];
+
+        match &self.reason {
+            ReportReason::Rule(reason) => {
+                form_params.push(("reason", reason.clone()));
+                form_params.push(("rule_reason", reason.clone()));
+            },
+            ReportReason::Site(reason) => {
+                form_params.push(("reason", reason.clone()));
+                form_params.push(("site_reason", reason.clone()));
+            },
+            ReportReason::Other(reason) => {
+                form_params.push(("reason", "other".to_string()));
+                form_params.push(("other_reason", reason.clone()));
+            }
+        }
+
+        if let Some(custom_text) = &self.custom_text {
+            form_params.push(("custom_text", custom_text.clone()));
+        }
+
+        if let Some(sr_name) = &self.sr_name {
+            form_params.push(("sr_name", sr_name.clone()));
+        }
+
+        Ok(client.http_client
+            .post(format!("{}api/report", REDDIT_API_URL))
+            .bearer_auth(client.access_token()?)
+            .form(&form_params))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<(), Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        check_api_errors(&v)
+    }
+}
+
+pub struct ReportRequestBuilder {
+    req: ReportRequest
+}
+
+impl ReportRequestBuilder {
+    // Reports the thing with fullname `thing_id`, e.g. a post, comment or private message.
+    pub fn new(thing_id: &str, reason: ReportReason) -> Result<Self, Error> {
+        if !["t1_", "t2_", "t3_", "t4_", "t5_"].iter().any(|prefix| { thing_id.len() > 3 && thing_id.starts_with(prefix) }) {
+            return Err(Error::UserError(format!("Not a reportable fullname: {}", thing_id)));
+        }
+        if reason.text().is_empty() || reason.text().chars().count() > MAX_REASON_LENGTH {
+            return Err(Error::UserError(format!(
+                        "Reason must be between 1 and {} characters long: {:?}",
+                        MAX_REASON_LENGTH,
+                        reason.text())));
+        }
+        Ok(ReportRequestBuilder {
+            req: ReportRequest {
+                thing_id: thing_id.to_string(),
+                reason,
+                custom_text: None,
+                sr_name: None
+            }
+        })
+    }
+
+    // Extra text for rules that let reporters explain themselves.
+    pub fn custom_text(mut self, custom_text: &str) -> Result<Self, Error> {
+        if custom_text.chars().count() > MAX_CUSTOM_TEXT_LENGTH {
+            return Err(Error::UserError(format!(
+                        "Custom text cannot be longer than {} characters.",
+                        MAX_CUSTOM_TEXT_LENGTH)));
+        }
+        self.req.custom_text = Some(custom_text.to_string());
+        Ok(self)
+    }
+
+    // Subreddit the reported thing is in.
+    pub fn sr_name(mut self, sr_name: &str) -> Self {
+        self.req.sr_name = Some(sr_name.to_string());
+        self
+    }
+}
+
+impl RequestBuilder<ReportRequest> for ReportRequestBuilder {
+    fn build(&self) -> ReportRequest {
+        self.req.clone()
+    }
+}
+
+// Reasons that can be given when reporting things in a subreddit.
+#[derive(Clone, Default, Debug)]
+pub struct ReportReasons {
+    // Violation reasons of the subreddit's own rules.
+    pub rules: Vec<String>,
+    // Reddit's site-wide rules.
+    pub site_rules: Vec<String>
+}
+
+impl ReportReasons {
+    pub(crate) fn from_rules(rules: SubredditRules) -> Self {
+        ReportReasons {
+            rules: rules.rules.into_iter()
+                .map(|rule| {
+                    // Rules without an explicit violation reason are reported by their name.
+                    match rule.violation_reason.is_empty() {
+                        true => rule.short_name,
+                        false => rule.violation_reason
+                    }
+                })
+                .collect(),
+            site_rules: rules.site_rules
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ReportReasonsRequest {
+    subreddit: String
+}
+
+impl ReportReasonsRequest {
+    pub fn new(subreddit: &str) -> Self {
+        ReportReasonsRequest { subreddit: subreddit.to_string() }
+    }
+}
+
+#[async_trait]
+impl Request<ReportReasons> for ReportReasonsRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        SubredditRulesRequest::new(&self.subreddit).get_filled_builder(client)
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<ReportReasons, Error> {
+        Ok(ReportReasons::from_rules(SubredditRulesRequest::new(&self.subreddit).send(client).await?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use serde_json::json;
+    use super::*;
+    use crate::api::util::{ test_body, test_client };
+
+    #[test]
+    fn test_report_request() -> Result<()> {
+        let req = ReportRequestBuilder::new("t3_abc", ReportReason::Rule("No memes".to_string()))?
+            .custom_text("Third one today")?
+            .sr_name("rust")
+            .build()
+            .construct(&test_client())?;
+
+        assert_eq!(req.url().path(), "/api/report");
+        assert_eq!(
+            test_body(&req),
+            "api_type=json&thing_id=t3_abc&reason=No+memes&rule_reason=No+memes&custom_text=Third+one+today&sr_name=rust"
+        );
+
+        let req = ReportRequestBuilder::new("t1_def", ReportReason::Other("Spam bot".to_string()))?
+            .build()
+            .construct(&test_client())?;
+        assert_eq!(test_body(&req), "api_type=json&thing_id=t1_def&reason=other&other_reason=Spam+bot");
+
+        assert!(ReportRequestBuilder::new("t1_def", ReportReason::Site(String::new())).is_err());
+        assert!(ReportRequestBuilder::new("def", ReportReason::Other("Spam bot".to_string())).is_err());
+        assert!(ReportRequestBuilder::new("t6_def", ReportReason::Other("Spam bot".to_string())).is_err());
+        assert!(ReportRequestBuilder::new("t1_", ReportReason::Other("Spam bot".to_string())).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_report_reasons() -> Result<()> {
+        let v = json!({
+            "rules": [
+                { "kind": "all", "short_name": "Be civil", "violation_reason": "Incivility" },
+                { "kind": "link", "short_name": "No memes", "violation_reason": "" }
+            ],
+            "site_rules": ["Spam", "Personal and confidential information"]
+        });
+
+        let reasons = ReportReasons::from_rules(SubredditRules::from_value(&v)?);
+        assert_eq!(reasons.rules, vec!["Incivility", "No memes"]);
+        assert_eq!(reasons.site_rules, vec!["Spam", "Personal and confidential information"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,226 @@
+use reqwest::RequestBuilder as HTTPRequestBuilder;
+use async_trait::async_trait;
+
+use super::util::{ send_for_json, Request, RequestBuilder, Error };
+use super::user::{ UserHistory, UserListingRequest, UserListingRequestBuilder };
+use crate::REDDIT_API_URL;
+use super::util::RedditClient;
+
+fn assert_comment_or_post(id: &str) -> Result<(), Error> {
+    match id.starts_with("t1_") || id.starts_with("t3_") {
+        true => Ok(()),
+        false => Err(Error::UserError(format!("Not a comment or post fullname: {}", id)))
+    }
+}
+
+#[derive(Clone)]
+pub struct SaveRequest {
+    id: String,
+    category: Option<String>,
+    unsave: bool
+}
+
+impl SaveRequest {
+    // Saves the post or comment with fullname `id`.
+    pub fn save(id: &str) -> Result<Self, Error> {
+        assert_comment_or_post(id)?;
+        Ok(SaveRequest {
+            id: id.to_string(),
+            category: None,
+            unsave: false
+        })
+    }
+
+    // Saves into one of the user's saved categories, a Reddit Premium feature.
+    pub fn save_in_category(id: &str, category: &str) -> Result<Self, Error> {
+        let mut req = SaveRequest::save(id)?;
+        req.category = Some(category.to_string());
+        Ok(req)
+    }
+
+    pub fn unsave(id: &str) -> Result<Self, Error> {
+        assert_comment_or_post(id)?;
+        Ok(SaveRequest {
+            id: id.to_string(),
+            category: None,
+            unsave: true
+        })
+    }
+}
+
+#[async_trait]
+impl Request<()> for SaveRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        let mut form_params: Vec<(&str, String)> = vec![("id", self.id.clone())];
+
+        if let Some(category) = &self.category {
+            form_params.push(("category", category.clone()));
+        }
+
+        let path = match self.unsave {
+            true => "api/unsave",
+            false => "api/save"
+        };
+
+        Ok(client.http_client
+            .post(format!("{}{}", REDDIT_API_URL, path))
+            .bearer_auth(client.access_token()?)
+            .form(&form_params))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<(), Error> {
+        send_for_json(self.get_filled_builder(client)?).await?;
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+pub struct HideRequest {
+    ids: Vec<String>,
+    unhide: bool
+}
+
+impl HideRequest {
+    // Hides the posts with the given fullnames from the user's listings.
+    pub fn hide(ids: Vec<String>) -> Result<Self, Error> {
+        if let Some(id) = ids.iter().find(|id| { !id.starts_with("t3_") }) {
+            return Err(Error::UserError(format!("Not a post fullname: {}", id)));
+        }
+        Ok(HideRequest { ids, unhide: false })
+    }
+
+    pub fn unhide(ids: Vec<String>) -> Result<Self, Error> {
+        let mut req = HideRequest::hide(ids)?;
+        req.unhide = true;
+        Ok(req)
+    }
+}
+
+#[async_trait]
+impl Request<()> for HideRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        let path = match self.unhide {
+            true => "api/unhide",
+            false => "api/hide"
+        };
+
+        Ok(client.http_client
+            .post(format!("{}{}", REDDIT_API_URL, path))
+            .bearer_auth(client.access_token()?)
+            .form(&[("id", self.ids.join(","))]))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<(), Error> {
+        send_for_json(self.get_filled_builder(client)?).await?;
+        Ok(())
+    }
+}
+
+// Names of the categories the user has saved things into.
+#[derive(Clone)]
+pub struct SavedCategoriesRequest;
+
+#[async_trait]
+impl Request<Vec<String>> for SavedCategoriesRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        Ok(client.http_client
+            .get(format!("{}api/saved_categories", REDDIT_API_URL))
+            .bearer_auth(client.access_token()?))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<Vec<String>, Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        Ok(v["categories"].as_array()
+            .map_or_else(Vec::new, |categories| {
+                categories.iter()
+                    .filter_map(|category| { category["category"].as_str().map(|c| { c.to_string() }) })
+                    .collect()
+            }))
+    }
+}
+
+// Posts and comments saved by `username`, which must be the logged in user.
+pub type SavedRequest = UserListingRequest;
+
+// Shorthand for UserListingRequestBuilder with UserHistory::Saved.
+pub struct SavedRequestBuilder {
+    builder: UserListingRequestBuilder
+}
+
+impl SavedRequestBuilder {
+    pub fn new(username: &str) -> Self {
+        SavedRequestBuilder { builder: UserListingRequestBuilder::new(username, UserHistory::Saved) }
+    }
+
+    // Only list things saved into `category`.
+    pub fn category(mut self, category: &str) -> Self {
+        self.builder = self.builder.saved_category(category);
+        self
+    }
+
+    pub fn after(mut self, after: String) -> Self {
+        self.builder = self.builder.after(after);
+        self
+    }
+
+    pub fn before(mut self, before: String) -> Self {
+        self.builder = self.builder.before(before);
+        self
+    }
+
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.builder = self.builder.limit(limit);
+        self
+    }
+
+    pub fn count(mut self, count: u32) -> Self {
+        self.builder = self.builder.count(count);
+        self
+    }
+}
+
+impl RequestBuilder<SavedRequest> for SavedRequestBuilder {
+    fn build(&self) -> SavedRequest {
+        self.builder.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use super::*;
+    use crate::api::util::{ test_body, test_client };
+
+    #[test]
+    fn test_save_and_hide_requests() -> Result<()> {
+        let client = test_client();
+
+        let req = SaveRequest::save_in_category("t1_abc", "recipes")?.construct(&client)?;
+        assert_eq!(req.url().path(), "/api/save");
+        assert_eq!(test_body(&req), "id=t1_abc&category=recipes");
+
+        let req = SaveRequest::unsave("t3_def")?.construct(&client)?;
+        assert_eq!(req.url().path(), "/api/unsave");
+        assert_eq!(test_body(&req), "id=t3_def");
+        assert!(SaveRequest::save("t5_abc").is_err());
+
+        let req = HideRequest::unhide(vec!["t3_a".to_string(), "t3_b".to_string()])?.construct(&client)?;
+        assert_eq!(req.url().path(), "/api/unhide");
+        assert_eq!(test_body(&req), "id=t3_a%2Ct3_b");
+        assert!(HideRequest::hide(vec!["t1_a".to_string()]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_saved_request() -> Result<()> {
+        let req = SavedRequestBuilder::new("spez")
+            .category("recipes")
+            .limit(25)
+            .build()
+            .construct(&test_client())?;
+
+        assert_eq!(req.url().path(), "/user/spez/saved");
+        assert_eq!(req.url().query(), Some("limit=25&category=recipes"));
+        Ok(())
+    }
+}
@@ -0,0 +1,271 @@
+use reqwest::RequestBuilder as HTTPRequestBuilder;
+use async_trait::async_trait;
+use serde_json::Value;
+
+use super::util::{ send_for_json, Request, RequestBuilder, Error };
+use super::listing::{ Pagination, SortTime };
+use super::thing::ThingListing;
+use crate::REDDIT_API_URL;
+use super::util::RedditClient;
+
+#[derive(Clone, Debug)]
+pub enum SearchSort {
+    Relevance,
+    Hot,
+    Top,
+    New,
+    Comments
+}
+
+impl SearchSort {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SearchSort::Relevance => "relevance",
+            SearchSort::Hot => "hot",
+            SearchSort::Top => "top",
+            SearchSort::New => "new",
+            SearchSort::Comments => "comments"
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SearchType {
+    Link,
+    Subreddit,
+    User
+}
+
+impl SearchType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SearchType::Link => "link",
+            SearchType::Subreddit => "sr",
+            SearchType::User => "user"
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SearchRequest {
+    q: String,
+    subreddit: Option<String>,
+    // Defaults to whether a subreddit is given.
+    restrict_sr: Option<bool>,
+    sort: Option<SearchSort>,
+    t: Option<SortTime>,
+    types: Vec<SearchType>,
+    include_over_18: bool,
+    pagination: Pagination
+}
+
+#[async_trait]
+impl Request<Vec<ThingListing>> for SearchRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        if self.restrict_sr == Some(true) && self.subreddit.is_none() {
+            return Err(Error::UserError("restrict_sr needs a subreddit to search in.".to_string()));
+        }
+
+        let mut query_params: Vec<(&str, String)> = vec![("q", self.q.clone())];
+
+        self.pagination.push_query_params(&mut query_params);
+
+        if self.restrict_sr.unwrap_or(self.subreddit.is_some()) {
+            query_params.push(("restrict_sr", "true".to_string()));
+        }
+
+        if let Some(sort) = &self.sort {
+            query_params.push(("sort", sort.as_str().to_string()));
+        }
+
+        if let Some(t_sort_time) = &self.t {
+            query_params.push(("t", t_sort_time.as_str().to_string()));
+        }
+
+        if !self.types.is_empty() {
+            query_params.push(("type", self.types.iter()
+                               .map(|search_type| { search_type.as_str() })
+                               .collect::<Vec<&str>>()
+                               .join(",")));
+        }
+
+        if self.include_over_18 {
+            query_params.push(("include_over_18", "on".to_string()));
+        }
+
+        let path = match &self.subreddit {
+            Some(subreddit) => format!("r/{}/search", subreddit),
+            None => "search".to_string()
+        };
+
+        Ok(client.http_client
+            .get(format!("{}{}", REDDIT_API_URL, path))
+            .bearer_auth(client.access_token()?)
+            .query(&query_params))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<Vec<ThingListing>, Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        parse_search_results(&v)
+    }
+}
+
+// Searching for several types at once returns one listing per type instead of a single listing.
+// Each listing keeps its own `after`/`before` cursor, since they page independently.
+fn parse_search_results(v: &Value) -> Result<Vec<ThingListing>, Error> {
+    match v.as_array() {
+        Some(listings) => listings.iter().map(ThingListing::from_value).collect(),
+        None => Ok(vec![ThingListing::from_value(v)?])
+    }
+}
+
+pub struct SearchRequestBuilder {
+    req: SearchRequest
+}
+
+impl SearchRequestBuilder {
+    pub fn new(q: &str) -> Self {
+        SearchRequestBuilder {
+            req: SearchRequest {
+                q: q.to_string(),
+                subreddit: None,
+                restrict_sr: None,
+                sort: None,
+                t: None,
+                types: Vec::new(),
+                include_over_18: false,
+                pagination: Pagination::default()
+            }
+        }
+    }
+
+    // Searches from `subreddit`, and only within it unless `restrict_sr(false)` is set.
+    pub fn subreddit(mut self, subreddit: &str) -> Self {
+        self.req.subreddit = Some(subreddit.to_string());
+        self
+    }
+
+    // Whether to only return results from the subreddit searched from. Defaults to true when a
+    // subreddit is given; setting it to true without a subreddit is an error.
+    pub fn restrict_sr(mut self, restrict_sr: bool) -> Self {
+        self.req.restrict_sr = Some(restrict_sr);
+        self
+    }
+
+    pub fn sort(mut self, sort: SearchSort) -> Self {
+        self.req.sort = Some(sort);
+        self
+    }
+
+    pub fn t(mut self, sort_time: SortTime) -> Self {
+        self.req.t = Some(sort_time);
+        self
+    }
+
+    pub fn search_type(mut self, search_type: SearchType) -> Self {
+        if !self.req.types.contains(&search_type) {
+            self.req.types.push(search_type);
+        }
+        self
+    }
+
+    pub fn include_over_18(mut self, include_over_18: bool) -> Self {
+        self.req.include_over_18 = include_over_18;
+        self
+    }
+
+    pub fn after(mut self, after: String) -> Self {
+        self.req.pagination.after = Some(after);
+        self
+    }
+
+    pub fn before(mut self, before: String) -> Self {
+        self.req.pagination.before = Some(before);
+        self
+    }
+
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.req.pagination.limit = Some(limit);
+        self
+    }
+
+    pub fn count(mut self, count: u32) -> Self {
+        self.req.pagination.count = Some(count);
+        self
+    }
+}
+
+impl RequestBuilder<SearchRequest> for SearchRequestBuilder {
+    fn build(&self) -> SearchRequest {
+        self.req.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use serde_json::json;
+    use super::*;
+    use crate::api::thing::Thing;
+    use crate::api::util::test_client;
+
+    #[test]
+    fn test_search_request() -> Result<()> {
+        let req = SearchRequestBuilder::new("async traits")
+            .subreddit("rust")
+            .sort(SearchSort::Top)
+            .t(SortTime::Week)
+            .search_type(SearchType::Link)
+            .search_type(SearchType::Link)
+            .include_over_18(true)
+            .after("t3_abc".to_string())
+            .limit(10)
+            .build()
+            .construct(&test_client())?;
+
+        assert_eq!(req.url().path(), "/r/rust/search");
+        assert_eq!(
+            req.url().query(),
+            Some("q=async+traits&after=t3_abc&limit=10&restrict_sr=true&sort=top&t=week&type=link&include_over_18=on")
+        );
+
+        let req = SearchRequestBuilder::new("borrow checker")
+            .restrict_sr(false)
+            .subreddit("rust")
+            .build()
+            .construct(&test_client())?;
+        assert_eq!(req.url().path(), "/r/rust/search");
+        assert_eq!(req.url().query(), Some("q=borrow+checker"));
+
+        let req = SearchRequestBuilder::new("rust").build().construct(&test_client())?;
+        assert_eq!(req.url().path(), "/search");
+
+        assert!(SearchRequestBuilder::new("rust").restrict_sr(true).build().construct(&test_client()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_multi_type_results() -> Result<()> {
+        let v = json!([
+            { "kind": "Listing", "data": { "after": null, "children": [
+                { "kind": "t5", "data": { "id": "2fwo", "name": "t5_2fwo", "display_name": "rust" } }
+            ] } },
+            { "kind": "Listing", "data": { "after": "t3_a", "children": [
+                { "kind": "t3", "data": { "id": "a", "name": "t3_a", "subreddit": "rust", "title": "A", "selftext": "", "score": 1 } }
+            ] } }
+        ]);
+
+        let listings = parse_search_results(&v)?;
+        assert_eq!(listings.len(), 2);
+        assert_eq!(listings[0].after, "");
+        assert!(matches!(listings[0].things[0], Thing::Subreddit(_)));
+        assert_eq!(listings[1].after, "t3_a");
+        assert!(matches!(listings[1].things[0], Thing::Post(_)));
+
+        let v = json!({ "kind": "Listing", "data": { "after": "t3_a", "children": [] } });
+        let listings = parse_search_results(&v)?;
+        assert_eq!(listings.len(), 1);
+        assert_eq!(listings[0].after, "t3_a");
+        Ok(())
+    }
+}
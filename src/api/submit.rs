@@ -0,0 +1,416 @@
+use reqwest::RequestBuilder as HTTPRequestBuilder;
+use async_trait::async_trait;
+use serde_json::{ json, Value };
+
+use super::util::{
+    check_api_errors,
+    extract_string_with_default,
+    send_for_json,
+    Request,
+    RequestBuilder,
+    Error
+};
+use super::media::GalleryItem;
+use crate::REDDIT_API_URL;
+use super::util::RedditClient;
+
+const MAX_TITLE_LENGTH: usize = 300;
+const MAX_CAPTION_LENGTH: usize = 180;
+const MIN_GALLERY_ITEMS: usize = 2;
+const MAX_GALLERY_ITEMS: usize = 20;
+const MIN_POLL_OPTIONS: usize = 2;
+const MAX_POLL_OPTIONS: usize = 6;
+const MAX_POLL_DURATION: u32 = 7;
+
+#[derive(Clone, Debug)]
+pub enum SubmitKind {
+    // Text post with the given markdown body, which may be empty.
+    SelfPost(String),
+    // Link post to the given URL.
+    Link(String),
+    // Crosspost of the post with the given fullname.
+    Crosspost(String),
+    // Image post of media uploaded with `media::upload_media`, given its URL.
+    Image(String),
+    // Video post of uploaded media, with an uploaded image shown before playback.
+    Video { url: String, poster_url: String },
+    // Gallery of 2 to 20 uploaded images.
+    Gallery(Vec<GalleryItem>),
+    // Poll with a markdown body, 2 to 6 options and voting open for 1 to 7 days.
+    Poll { text: String, options: Vec<String>, duration: u32 }
+}
+
+// The post created by a submission. Image and video posts are created asynchronously once Reddit
+// has processed the media, so their `id` and `name` are empty and the post is announced on
+// `websocket_url` instead.
+#[derive(Clone, Default, Debug)]
+pub struct Submission {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    pub websocket_url: String
+}
+
+impl Submission {
+    // Parses the `json.data` object of a submit response. Gallery submissions return the fullname
+    // in `id` and no `name`.
+    pub(crate) fn from_data(data: &Value) -> Result<Self, Error> {
+        let id = extract_string_with_default!(data["id"], "json.data.id");
+        let name = match data["name"].as_str() {
+            Some(name) => name.to_string(),
+            None if id.starts_with("t3_") => id.clone(),
+            None if !id.is_empty() => format!("t3_{}", id),
+            None => String::new()
+        };
+        let websocket_url = extract_string_with_default!(data["websocket_url"], "json.data.websocket_url");
+        if name.is_empty() && websocket_url.is_empty() {
+            return Err(Error::InternalError("Failed to parse to string: json.data.name".to_string()));
+        }
+
+        Ok(Submission {
+            id: id.trim_start_matches("t3_").to_string(),
+            name,
+            url: match data["url"].as_str() {
+                Some(url) => url.to_string(),
+                None => extract_string_with_default!(data["user_submitted_page"], "json.data.user_submitted_page")
+            },
+            websocket_url
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct SubmitRequest {
+    subreddit: String,
+    title: String,
+    kind: SubmitKind,
+    flair_id: Option<String>,
+    flair_text: Option<String>,
+    nsfw: bool,
+    spoiler: bool,
+    sendreplies: bool,
+    resubmit: bool
+}
+
+impl SubmitRequest {
+    // Form fields common to every kind of submission.
+    pub(crate) fn common_form_params(&self) -> Vec<(&str, String)> {
+        let mut form_params: Vec<(&str, String)> = vec![
+            ("api_type", "json".to_string()),
+            ("sr", self.subreddit.clone()),
+            ("title", self.title.clone()),
+            ("nsfw", self.nsfw.to_string()),
+            ("spoiler", self.spoiler.to_string()),
+            ("sendreplies", self.sendreplies.to_string()),
+            ("resubmit", self.resubmit.to_string())
+        ];
+
+        if let Some(flair_id) = &self.flair_id {
+            form_params.push(("flair_id", flair_id.clone()));
+        }
+
+        if let Some(flair_text) = &self.flair_text {
+            form_params.push(("flair_text", flair_text.clone()));
+        }
+
+        form_params
+    }
+}
+
+#[async_trait]
+impl Request<Submission> for SubmitRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        // Galleries and polls have their own endpoints, which take JSON rather than a form.
+        let json_submission = match &self.kind {
+            SubmitKind::Gallery(items) => Some(("api/submit_gallery_post.json", json!({
+                "items": items.iter().map(|item| {
+                    json!({
+                        "media_id": item.media_id,
+                        "caption": item.caption,
+                        "outbound_url": item.outbound_url
+                    })
+                }).collect::<Vec<Value>>()
+            }))),
+            SubmitKind::Poll { text, options, duration } => Some(("api/submit_poll_post.json", json!({
+                "text": text,
+                "options": options,
+                "duration": duration
+            }))),
+            _ => None
+        };
+
+        if let Some((path, mut body)) = json_submission {
+            body["api_type"] = json!("json");
+            body["sr"] = json!(self.subreddit);
+            body["title"] = json!(self.title);
+            body["nsfw"] = json!(self.nsfw);
+            body["spoiler"] = json!(self.spoiler);
+            body["sendreplies"] = json!(self.sendreplies);
+            body["flair_id"] = json!(self.flair_id);
+            body["flair_text"] = json!(self.flair_text);
+
+            return Ok(client.http_client
+                .post(format!("{}{}", REDDIT_API_URL, path))
+                .bearer_auth(client.access_token()?)
+                .json(&body));
+        }
+
+        let mut form_params = self.common_form_params();
+
+        match &self.kind {
+            SubmitKind::SelfPost(text) => {
+                form_params.push(("kind", "self".to_string()));
+                form_params.push(("text", text.clone()));
+            },
+            SubmitKind::Link(url) => {
+                form_params.push(("kind", "link".to_string()));
+                form_params.push(("url", url.clone()));
+            },
+            SubmitKind::Crosspost(fullname) => {
+                form_params.push(("kind", "crosspost".to_string()));
+                form_params.push(("crosspost_fullname", fullname.clone()));
+            },
+            SubmitKind::Image(url) => {
+                form_params.push(("kind", "image".to_string()));
+                form_params.push(("url", url.clone()));
+            },
+            SubmitKind::Video { url, poster_url } => {
+                form_params.push(("kind", "video".to_string()));
+                form_params.push(("url", url.clone()));
+                form_params.push(("video_poster_url", poster_url.clone()));
+            },
+            SubmitKind::Gallery(_) | SubmitKind::Poll { .. } => {
+                return Err(Error::InternalError("Gallery and poll submissions are not sent as forms.".to_string()));
+            }
+        }
+
+        Ok(client.http_client
+            .post(format!("{}api/submit", REDDIT_API_URL))
+            .bearer_auth(client.access_token()?)
+            .form(&form_params))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<Submission, Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        check_api_errors(&v)?;
+        Submission::from_data(&v["json"]["data"])
+    }
+}
+
+pub struct SubmitRequestBuilder {
+    req: SubmitRequest
+}
+
+impl SubmitRequestBuilder {
+    pub fn new(subreddit: &str, title: &str, kind: SubmitKind) -> Result<Self, Error> {
+        if title.is_empty() || title.chars().count() > MAX_TITLE_LENGTH {
+            return Err(Error::UserError(format!(
+                        "Title must be between 1 and {} characters long: {:?}",
+                        MAX_TITLE_LENGTH,
+                        title)));
+        }
+        match &kind {
+            SubmitKind::Crosspost(fullname) if !fullname.starts_with("t3_") => {
+                return Err(Error::UserError(format!("Not a post fullname: {}", fullname)));
+            },
+            SubmitKind::Gallery(items) => {
+                if items.len() < MIN_GALLERY_ITEMS || items.len() > MAX_GALLERY_ITEMS {
+                    return Err(Error::UserError(format!(
+                                "Galleries must have between {} and {} items: {}",
+                                MIN_GALLERY_ITEMS,
+                                MAX_GALLERY_ITEMS,
+                                items.len())));
+                }
+                if let Some(item) = items.iter().find(|item| { item.caption.chars().count() > MAX_CAPTION_LENGTH }) {
+                    return Err(Error::UserError(format!(
+                                "Captions cannot be longer than {} characters: {:?}",
+                                MAX_CAPTION_LENGTH,
+                                item.caption)));
+                }
+            },
+            SubmitKind::Poll { options, duration, .. } => {
+                if options.len() < MIN_POLL_OPTIONS || options.len() > MAX_POLL_OPTIONS {
+                    return Err(Error::UserError(format!(
+                                "Polls must have between {} and {} options: {}",
+                                MIN_POLL_OPTIONS,
+                                MAX_POLL_OPTIONS,
+                                options.len())));
+                }
+                if *duration < 1 || *duration > MAX_POLL_DURATION {
+                    return Err(Error::UserError(format!(
+                                "Poll duration must be between 1 and {} days: {}",
+                                MAX_POLL_DURATION,
+                                duration)));
+                }
+            },
+            _ => {}
+        }
+
+        Ok(SubmitRequestBuilder {
+            req: SubmitRequest {
+                subreddit: subreddit.to_string(),
+                title: title.to_string(),
+                kind,
+                flair_id: None,
+                flair_text: None,
+                nsfw: false,
+                spoiler: false,
+                sendreplies: true,
+                resubmit: false
+            }
+        })
+    }
+
+    pub fn flair_id(mut self, flair_id: &str) -> Self {
+        self.req.flair_id = Some(flair_id.to_string());
+        self
+    }
+
+    pub fn flair_text(mut self, flair_text: &str) -> Self {
+        self.req.flair_text = Some(flair_text.to_string());
+        self
+    }
+
+    pub fn nsfw(mut self, nsfw: bool) -> Self {
+        self.req.nsfw = nsfw;
+        self
+    }
+
+    pub fn spoiler(mut self, spoiler: bool) -> Self {
+        self.req.spoiler = spoiler;
+        self
+    }
+
+    // Whether replies to the post are sent to the author's inbox. Defaults to true.
+    pub fn sendreplies(mut self, sendreplies: bool) -> Self {
+        self.req.sendreplies = sendreplies;
+        self
+    }
+
+    // Submit a link even if it was already submitted to the subreddit.
+    pub fn resubmit(mut self, resubmit: bool) -> Self {
+        self.req.resubmit = resubmit;
+        self
+    }
+}
+
+impl RequestBuilder<SubmitRequest> for SubmitRequestBuilder {
+    fn build(&self) -> SubmitRequest {
+        self.req.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use super::*;
+    use crate::api::util::{ test_body, test_client, ApiError };
+
+    #[test]
+    fn test_submit_request() -> Result<()> {
+        let req = SubmitRequestBuilder::new("test", "Hello", SubmitKind::Link("https://example.com".to_string()))?
+            .flair_id("abc")
+            .nsfw(true)
+            .resubmit(true)
+            .build()
+            .construct(&test_client())?;
+
+        assert_eq!(req.method(), "POST");
+        assert_eq!(req.url().path(), "/api/submit");
+        assert_eq!(
+            test_body(&req),
+            "api_type=json&sr=test&title=Hello&nsfw=true&spoiler=false&sendreplies=true&resubmit=true\
+            &flair_id=abc&kind=link&url=https%3A%2F%2Fexample.com"
+        );
+
+        assert!(SubmitRequestBuilder::new("test", "", SubmitKind::SelfPost(String::new())).is_err());
+        assert!(SubmitRequestBuilder::new("test", "Title", SubmitKind::Crosspost("t1_a".to_string())).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_gallery_submit_request() -> Result<()> {
+        let items = vec![
+            GalleryItem::new("asset1").caption("First"),
+            GalleryItem::new("asset2").outbound_url("https://example.com")
+        ];
+        let req = SubmitRequestBuilder::new("test", "Gallery", SubmitKind::Gallery(items))?
+            .build()
+            .construct(&test_client())?;
+
+        assert_eq!(req.url().path(), "/api/submit_gallery_post.json");
+        let body: Value = serde_json::from_str(&test_body(&req))?;
+        assert_eq!(body["items"][0], json!({ "media_id": "asset1", "caption": "First", "outbound_url": "" }));
+        assert_eq!(body["items"][1]["outbound_url"], "https://example.com");
+        assert_eq!(body["nsfw"], false);
+
+        assert!(SubmitRequestBuilder::new("test", "Gallery", SubmitKind::Gallery(vec![GalleryItem::new("a")])).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_poll_submit_request() -> Result<()> {
+        let kind = SubmitKind::Poll {
+            text: "Which one?".to_string(),
+            options: vec!["A".to_string(), "B".to_string()],
+            duration: 3
+        };
+        let req = SubmitRequestBuilder::new("test", "Poll", kind)?
+            .flair_text("Question")
+            .build()
+            .construct(&test_client())?;
+
+        assert_eq!(req.url().path(), "/api/submit_poll_post.json");
+        let body: Value = serde_json::from_str(&test_body(&req))?;
+        assert_eq!(body["options"], json!(["A", "B"]));
+        assert_eq!(body["duration"], 3);
+        assert_eq!(body["text"], "Which one?");
+        assert_eq!(body["flair_text"], "Question");
+
+        let kind = SubmitKind::Poll { text: String::new(), options: vec!["A".to_string(), "B".to_string()], duration: 8 };
+        assert!(SubmitRequestBuilder::new("test", "Poll", kind).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_submit_errors() -> Result<()> {
+        let v = json!({ "json": { "errors": [
+            ["SUBREDDIT_NOEXIST", "that subreddit doesn't exist", "sr"],
+            ["RATELIMIT", "you are doing that too much", null]
+        ] } });
+
+        match check_api_errors(&v) {
+            Err(Error::ApiErrors(errors)) => assert_eq!(errors, vec![
+                ApiError {
+                    code: "SUBREDDIT_NOEXIST".to_string(),
+                    message: "that subreddit doesn't exist".to_string(),
+                    field: Some("sr".to_string())
+                },
+                ApiError {
+                    code: "RATELIMIT".to_string(),
+                    message: "you are doing that too much".to_string(),
+                    field: None
+                }
+            ]),
+            result => panic!("Expected ApiErrors, got {:?}", result)
+        }
+
+        let v = json!({ "json": { "errors": [], "data": {
+            "id": "abc", "name": "t3_abc", "url": "https://www.reddit.com/r/test/comments/abc/hello/"
+        } } });
+        check_api_errors(&v)?;
+        assert_eq!(Submission::from_data(&v["json"]["data"])?.name, "t3_abc");
+
+        let data = json!({ "id": "t3_def", "url": "https://www.reddit.com/gallery/def" });
+        let submission = Submission::from_data(&data)?;
+        assert_eq!((submission.id.as_str(), submission.name.as_str()), ("def", "t3_def"));
+
+        let data = json!({ "user_submitted_page": "https://www.reddit.com/user/me/submitted/", "websocket_url": "wss://ws" });
+        let submission = Submission::from_data(&data)?;
+        assert!(submission.name.is_empty());
+        assert_eq!(submission.websocket_url, "wss://ws");
+
+        assert!(Submission::from_data(&json!({})).is_err());
+        Ok(())
+    }
+}
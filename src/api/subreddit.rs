@@ -0,0 +1,769 @@
+use reqwest::RequestBuilder as HTTPRequestBuilder;
+use async_trait::async_trait;
+use serde_json::Value;
+
+use super::util::{
+    extract_string_with_default,
+    extract_string_with_error,
+    send_for_json,
+    Request,
+    RequestBuilder,
+    Error
+};
+use super::listing::Pagination;
+use crate::REDDIT_API_URL;
+use super::util::RedditClient;
+
+#[derive(Clone, Default, Debug)]
+pub struct Subreddit {
+    pub id: String,
+    pub name: String,
+    pub display_name: String,
+    pub title: String,
+    pub public_description: String,
+    // Sidebar text in markdown.
+    pub description: String,
+    pub url: String,
+    pub subscribers: u64,
+    // Only included by /about.
+    pub active_user_count: Option<u64>,
+    pub over18: bool,
+    // `public`, `restricted`, `private`, `archived`, `employees_only`, `gold_only` or `user`.
+    pub subreddit_type: String,
+    pub icon_img: String,
+    pub community_icon: String,
+    pub banner_img: String,
+    pub header_img: String,
+    pub created_utc: f64
+}
+
+impl Subreddit {
+    // Parses the `data` object of a `t5` thing.
+    pub(crate) fn from_data(data: &Value) -> Result<Self, Error> {
+        Ok(Subreddit {
+            id: extract_string_with_error!(data["id"], "data.id"),
+            name: extract_string_with_error!(data["name"], "data.name"),
+            display_name: extract_string_with_error!(data["display_name"], "data.display_name"),
+            title: extract_string_with_default!(data["title"], "data.title"),
+            public_description: extract_string_with_default!(data["public_description"], "data.public_description"),
+            description: extract_string_with_default!(data["description"], "data.description"),
+            url: extract_string_with_default!(data["url"], "data.url"),
+            subscribers: data["subscribers"].as_u64().unwrap_or_default(),
+            active_user_count: data["active_user_count"].as_u64(),
+            over18: data["over18"].as_bool().unwrap_or_default(),
+            subreddit_type: extract_string_with_default!(data["subreddit_type"], "data.subreddit_type"),
+            icon_img: extract_string_with_default!(data["icon_img"], "data.icon_img"),
+            // Reddit HTML-escapes some image URLs.
+            community_icon: extract_string_with_default!(data["community_icon"], "data.community_icon")
+                .replace("&amp;", "&"),
+            banner_img: extract_string_with_default!(data["banner_img"], "data.banner_img"),
+            header_img: extract_string_with_default!(data["header_img"], "data.header_img"),
+            created_utc: data["created_utc"].as_f64().unwrap_or_default()
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct SubredditAboutRequest {
+    subreddit: String
+}
+
+impl SubredditAboutRequest {
+    pub fn new(subreddit: &str) -> Self {
+        SubredditAboutRequest { subreddit: subreddit.to_string() }
+    }
+}
+
+#[async_trait]
+impl Request<Subreddit> for SubredditAboutRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        Ok(client.http_client
+            .get(format!("{}r/{}/about", REDDIT_API_URL, self.subreddit))
+            .bearer_auth(client.access_token()?))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<Subreddit, Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        Subreddit::from_data(&v["data"])
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct SubredditRule {
+    // `link`, `comment` or `all`: what the rule applies to.
+    pub kind: String,
+    pub short_name: String,
+    pub description: String,
+    // Shown to moderators in reports; empty if the short name is used instead.
+    pub violation_reason: String,
+    pub priority: u64,
+    pub created_utc: f64
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct SubredditRules {
+    pub rules: Vec<SubredditRule>,
+    // Reddit's site-wide rules.
+    pub site_rules: Vec<String>
+}
+
+impl SubredditRules {
+    pub(crate) fn from_value(v: &Value) -> Result<Self, Error> {
+        Ok(SubredditRules {
+            rules: v["rules"].as_array()
+                .ok_or(Error::InternalError("Can't convert to vector".to_string()))?
+                .iter()
+                .map(|rule| -> Result<SubredditRule, Error> {
+                    Ok(SubredditRule {
+                        kind: extract_string_with_default!(rule["kind"], "rules.kind"),
+                        short_name: extract_string_with_error!(rule["short_name"], "rules.short_name"),
+                        description: extract_string_with_default!(rule["description"], "rules.description"),
+                        violation_reason: extract_string_with_default!(rule["violation_reason"], "rules.violation_reason"),
+                        priority: rule["priority"].as_u64().unwrap_or_default(),
+                        created_utc: rule["created_utc"].as_f64().unwrap_or_default()
+                    })
+                })
+                .collect::<Result<Vec<SubredditRule>, Error>>()?,
+            site_rules: v["site_rules"].as_array()
+                .map_or_else(Vec::new, |site_rules| {
+                    site_rules.iter().filter_map(|rule| { rule.as_str().map(|r| { r.to_string() }) }).collect()
+                })
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct SubredditRulesRequest {
+    subreddit: String
+}
+
+impl SubredditRulesRequest {
+    pub fn new(subreddit: &str) -> Self {
+        SubredditRulesRequest { subreddit: subreddit.to_string() }
+    }
+}
+
+#[async_trait]
+impl Request<SubredditRules> for SubredditRulesRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        Ok(client.http_client
+            .get(format!("{}r/{}/about/rules", REDDIT_API_URL, self.subreddit))
+            .bearer_auth(client.access_token()?))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<SubredditRules, Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        SubredditRules::from_value(&v)
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct WidgetButton {
+    pub text: String,
+    pub url: String
+}
+
+#[derive(Clone, Debug)]
+pub enum WidgetKind {
+    TextArea { text: String },
+    Button { description: String, buttons: Vec<WidgetButton> },
+    // Names of the listed subreddits.
+    Community { subreddits: Vec<String> },
+    // URLs of the images.
+    Image { images: Vec<String> },
+    // Short names of the rules.
+    Rules { rules: Vec<String> },
+    IdCard { description: String, subscribers_count: u64, currently_viewing_count: u64 },
+    // Names of the moderators.
+    Moderators { moderators: Vec<String>, total_mods: u64 },
+    // Calendars, menus, custom widgets and anything newer. See Widget::raw.
+    Other(String)
+}
+
+#[derive(Clone, Debug)]
+pub struct Widget {
+    pub id: String,
+    pub short_name: String,
+    pub kind: WidgetKind,
+    // The widget as Reddit sent it, for details not covered by `kind`.
+    pub raw: Value
+}
+
+fn string_list(v: &Value, key: &str) -> Vec<String> {
+    v.as_array().map_or_else(Vec::new, |items| {
+        items.iter().filter_map(|item| { item[key].as_str().map(|s| { s.to_string() }) }).collect()
+    })
+}
+
+impl Widget {
+    pub(crate) fn from_value(id: &str, v: &Value) -> Result<Self, Error> {
+        let kind = extract_string_with_error!(v["kind"], "items.kind");
+        Ok(Widget {
+            id: id.to_string(),
+            short_name: extract_string_with_default!(v["shortName"], "items.shortName"),
+            kind: match kind.as_str() {
+                "textarea" => WidgetKind::TextArea {
+                    text: extract_string_with_default!(v["text"], "items.text")
+                },
+                "button" => WidgetKind::Button {
+                    description: extract_string_with_default!(v["description"], "items.description"),
+                    buttons: v["buttons"].as_array().map_or_else(Vec::new, |buttons| {
+                        buttons.iter().map(|button| {
+                            WidgetButton {
+                                text: extract_string_with_default!(button["text"], "items.buttons.text"),
+                                url: extract_string_with_default!(button["url"], "items.buttons.url")
+                            }
+                        }).collect()
+                    })
+                },
+                "community-list" => WidgetKind::Community { subreddits: string_list(&v["data"], "name") },
+                "image" => WidgetKind::Image { images: string_list(&v["data"], "url") },
+                "subreddit-rules" => WidgetKind::Rules { rules: string_list(&v["data"], "shortName") },
+                "id-card" => WidgetKind::IdCard {
+                    description: extract_string_with_default!(v["description"], "items.description"),
+                    subscribers_count: v["subscribersCount"].as_u64().unwrap_or_default(),
+                    currently_viewing_count: v["currentlyViewingCount"].as_u64().unwrap_or_default()
+                },
+                "moderators" => WidgetKind::Moderators {
+                    moderators: string_list(&v["mods"], "name"),
+                    total_mods: v["totalMods"].as_u64().unwrap_or_default()
+                },
+                _ => WidgetKind::Other(kind)
+            },
+            raw: v.clone()
+        })
+    }
+}
+
+// A subreddit's widgets, in the order they are laid out.
+#[derive(Clone, Default, Debug)]
+pub struct Widgets {
+    pub id_card: Option<Widget>,
+    pub moderators: Option<Widget>,
+    pub sidebar: Vec<Widget>,
+    pub topbar: Vec<Widget>
+}
+
+impl Widgets {
+    pub(crate) fn from_value(v: &Value) -> Result<Self, Error> {
+        let widget = |id: &Value| -> Result<Option<Widget>, Error> {
+            match id.as_str() {
+                Some(id) if v["items"][id].is_object() => Ok(Some(Widget::from_value(id, &v["items"][id])?)),
+                _ => Ok(None)
+            }
+        };
+        let widget_list = |order: &Value| -> Result<Vec<Widget>, Error> {
+            let mut widgets: Vec<Widget> = Vec::new();
+            for id in order.as_array().map_or_else(Vec::new, |order| { order.clone() }) {
+                if let Some(w) = widget(&id)? {
+                    widgets.push(w);
+                }
+            }
+            Ok(widgets)
+        };
+
+        Ok(Widgets {
+            id_card: widget(&v["layout"]["idCardWidget"])?,
+            moderators: widget(&v["layout"]["moderatorWidget"])?,
+            sidebar: widget_list(&v["layout"]["sidebar"]["order"])?,
+            topbar: widget_list(&v["layout"]["topbar"]["order"])?
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct WidgetsRequest {
+    subreddit: String
+}
+
+impl WidgetsRequest {
+    pub fn new(subreddit: &str) -> Self {
+        WidgetsRequest { subreddit: subreddit.to_string() }
+    }
+}
+
+#[async_trait]
+impl Request<Widgets> for WidgetsRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        Ok(client.http_client
+            .get(format!("{}r/{}/api/widgets", REDDIT_API_URL, self.subreddit))
+            .bearer_auth(client.access_token()?))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<Widgets, Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        Widgets::from_value(&v)
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct SubredditListing {
+    pub after: String,
+    pub before: String,
+    pub subreddits: Vec<Subreddit>
+}
+
+impl SubredditListing {
+    pub(crate) fn from_value(v: &Value) -> Result<Self, Error> {
+        Ok(SubredditListing {
+            after: extract_string_with_default!(v["data"]["after"], "data.after"),
+            before: extract_string_with_default!(v["data"]["before"], "data.before"),
+            subreddits: v["data"]["children"].as_array()
+                .ok_or(Error::InternalError("Can't convert to vector".to_string()))?
+                .iter()
+                .map(|v_subreddit| -> Result<Subreddit, Error> { Subreddit::from_data(&v_subreddit["data"]) })
+                .collect::<Result<Vec<Subreddit>, Error>>()?
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct SubscribeRequest {
+    sr_names: Vec<String>,
+    unsubscribe: bool,
+    skip_initial_defaults: bool
+}
+
+impl SubscribeRequest {
+    // Subscribes to every subreddit in `sr_names`, given by name.
+    pub fn subscribe(sr_names: Vec<String>) -> Result<Self, Error> {
+        if sr_names.is_empty() {
+            return Err(Error::UserError("No subreddits to subscribe to.".to_string()));
+        }
+        Ok(SubscribeRequest {
+            sr_names,
+            unsubscribe: false,
+            skip_initial_defaults: true
+        })
+    }
+
+    pub fn unsubscribe(sr_names: Vec<String>) -> Result<Self, Error> {
+        let mut req = SubscribeRequest::subscribe(sr_names)?;
+        req.unsubscribe = true;
+        Ok(req)
+    }
+
+    pub fn subscribe_one(sr_name: &str) -> Self {
+        SubscribeRequest {
+            sr_names: vec![sr_name.to_string()],
+            unsubscribe: false,
+            skip_initial_defaults: true
+        }
+    }
+
+    pub fn unsubscribe_one(sr_name: &str) -> Self {
+        SubscribeRequest {
+            unsubscribe: true,
+            ..SubscribeRequest::subscribe_one(sr_name)
+        }
+    }
+
+    // A new account's first subscription also subscribes it to Reddit's default subreddits,
+    // unless this is set. Defaults to true.
+    pub fn skip_initial_defaults(mut self, skip_initial_defaults: bool) -> Self {
+        self.skip_initial_defaults = skip_initial_defaults;
+        self
+    }
+}
+
+#[async_trait]
+impl Request<()> for SubscribeRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        let mut form_params: Vec<(&str, String)> = vec![
+            ("action", match self.unsubscribe {
+                true => "unsub".to_string(),
+                false => "sub".to_string()
+            }),
+            ("sr_name", self.sr_names.join(","))
+        ];
+
+        if !self.unsubscribe {
+            form_params.push(("skip_initial_defaults", self.skip_initial_defaults.to_string()));
+        }
+
+        Ok(client.http_client
+            .post(format!("{}api/subscribe", REDDIT_API_URL))
+            .bearer_auth(client.access_token()?)
+            .form(&form_params))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<(), Error> {
+        send_for_json(self.get_filled_builder(client)?).await?;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum MySubredditsType {
+    Subscriber,
+    Moderator,
+    Contributor
+}
+
+impl MySubredditsType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            MySubredditsType::Subscriber => "subscriber",
+            MySubredditsType::Moderator => "moderator",
+            MySubredditsType::Contributor => "contributor"
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct MySubredditsRequest {
+    where_type: MySubredditsType,
+    pagination: Pagination
+}
+
+#[async_trait]
+impl Request<SubredditListing> for MySubredditsRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        let mut query_params: Vec<(&str, String)> = Vec::new();
+
+        self.pagination.push_query_params(&mut query_params);
+
+        Ok(client.http_client
+            .get(format!("{}subreddits/mine/{}", REDDIT_API_URL, self.where_type.as_str()))
+            .bearer_auth(client.access_token()?)
+            .query(&query_params))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<SubredditListing, Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        SubredditListing::from_value(&v)
+    }
+}
+
+pub struct MySubredditsRequestBuilder {
+    req: MySubredditsRequest
+}
+
+impl MySubredditsRequestBuilder {
+    pub fn new(where_type: MySubredditsType) -> Self {
+        MySubredditsRequestBuilder {
+            req: MySubredditsRequest {
+                where_type,
+                pagination: Pagination::default()
+            }
+        }
+    }
+
+    pub fn after(mut self, after: String) -> Self {
+        self.req.pagination.after = Some(after);
+        self
+    }
+
+    pub fn before(mut self, before: String) -> Self {
+        self.req.pagination.before = Some(before);
+        self
+    }
+
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.req.pagination.limit = Some(limit);
+        self
+    }
+
+    pub fn count(mut self, count: u32) -> Self {
+        self.req.pagination.count = Some(count);
+        self
+    }
+}
+
+impl RequestBuilder<MySubredditsRequest> for MySubredditsRequestBuilder {
+    fn build(&self) -> MySubredditsRequest {
+        self.req.clone()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum SubredditSearchSort {
+    Relevance,
+    Activity
+}
+
+#[derive(Clone, Debug)]
+pub enum SubredditsWhere {
+    Popular,
+    New,
+    // Subreddits whose name or description matches the query.
+    Search(String, Option<SubredditSearchSort>)
+}
+
+#[derive(Clone)]
+pub struct SubredditsRequest {
+    where_type: SubredditsWhere,
+    pagination: Pagination
+}
+
+#[async_trait]
+impl Request<SubredditListing> for SubredditsRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        let mut query_params: Vec<(&str, String)> = Vec::new();
+
+        self.pagination.push_query_params(&mut query_params);
+
+        let path = match &self.where_type {
+            SubredditsWhere::Popular => "popular",
+            SubredditsWhere::New => "new",
+            SubredditsWhere::Search(q, sort) => {
+                query_params.push(("q", q.clone()));
+                if let Some(sort) = sort {
+                    query_params.push(("sort", match sort {
+                        SubredditSearchSort::Relevance => "relevance".to_string(),
+                        SubredditSearchSort::Activity => "activity".to_string()
+                    }));
+                }
+                "search"
+            }
+        };
+
+        Ok(client.http_client
+            .get(format!("{}subreddits/{}", REDDIT_API_URL, path))
+            .bearer_auth(client.access_token()?)
+            .query(&query_params))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<SubredditListing, Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        SubredditListing::from_value(&v)
+    }
+}
+
+pub struct SubredditsRequestBuilder {
+    req: SubredditsRequest
+}
+
+impl SubredditsRequestBuilder {
+    pub fn new(where_type: SubredditsWhere) -> Self {
+        SubredditsRequestBuilder {
+            req: SubredditsRequest {
+                where_type,
+                pagination: Pagination::default()
+            }
+        }
+    }
+
+    pub fn after(mut self, after: String) -> Self {
+        self.req.pagination.after = Some(after);
+        self
+    }
+
+    pub fn before(mut self, before: String) -> Self {
+        self.req.pagination.before = Some(before);
+        self
+    }
+
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.req.pagination.limit = Some(limit);
+        self
+    }
+
+    pub fn count(mut self, count: u32) -> Self {
+        self.req.pagination.count = Some(count);
+        self
+    }
+}
+
+impl RequestBuilder<SubredditsRequest> for SubredditsRequestBuilder {
+    fn build(&self) -> SubredditsRequest {
+        self.req.clone()
+    }
+}
+
+const MAX_AUTOCOMPLETE_LIMIT: u32 = 10;
+
+// Subreddits whose names start with what the user has typed so far.
+#[derive(Clone)]
+pub struct SubredditAutocompleteRequest {
+    query: String,
+    include_over_18: bool,
+    limit: Option<u32>
+}
+
+#[async_trait]
+impl Request<Vec<Subreddit>> for SubredditAutocompleteRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        let mut query_params: Vec<(&str, String)> = vec![
+            ("query", self.query.clone()),
+            ("include_over_18", self.include_over_18.to_string()),
+            ("include_profiles", "false".to_string()),
+            ("typeahead_active", "true".to_string())
+        ];
+
+        if let Some(limit) = self.limit {
+            query_params.push(("limit", limit.to_string()));
+        }
+
+        Ok(client.http_client
+            .get(format!("{}api/subreddit_autocomplete_v2", REDDIT_API_URL))
+            .bearer_auth(client.access_token()?)
+            .query(&query_params))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<Vec<Subreddit>, Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        Ok(SubredditListing::from_value(&v)?.subreddits)
+    }
+}
+
+pub struct SubredditAutocompleteRequestBuilder {
+    req: SubredditAutocompleteRequest
+}
+
+impl SubredditAutocompleteRequestBuilder {
+    pub fn new(query: &str) -> Result<Self, Error> {
+        if query.is_empty() {
+            return Err(Error::UserError("Autocomplete query cannot be empty.".to_string()));
+        }
+        Ok(SubredditAutocompleteRequestBuilder {
+            req: SubredditAutocompleteRequest {
+                query: query.to_string(),
+                include_over_18: false,
+                limit: None
+            }
+        })
+    }
+
+    pub fn include_over_18(mut self, include_over_18: bool) -> Self {
+        self.req.include_over_18 = include_over_18;
+        self
+    }
+
+    // At most 10 suggestions can be requested.
+    pub fn limit(mut self, limit: u32) -> Result<Self, Error> {
+        if limit == 0 || limit > MAX_AUTOCOMPLETE_LIMIT {
+            return Err(Error::UserError(format!(
+                        "Limit must be between 1 and {}: {}",
+                        MAX_AUTOCOMPLETE_LIMIT,
+                        limit)));
+        }
+        self.req.limit = Some(limit);
+        Ok(self)
+    }
+}
+
+impl RequestBuilder<SubredditAutocompleteRequest> for SubredditAutocompleteRequestBuilder {
+    fn build(&self) -> SubredditAutocompleteRequest {
+        self.req.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use serde_json::json;
+    use super::*;
+    use crate::api::util::{ test_body, test_client };
+
+    #[test]
+    fn test_parse_subreddit_about() -> Result<()> {
+        let client = test_client();
+        assert_eq!(SubredditAboutRequest::new("rust").construct(&client)?.url().path(), "/r/rust/about");
+        assert_eq!(SubredditRulesRequest::new("rust").construct(&client)?.url().path(), "/r/rust/about/rules");
+        assert_eq!(WidgetsRequest::new("rust").construct(&client)?.url().path(), "/r/rust/api/widgets");
+
+        let v = json!({ "kind": "t5", "data": {
+            "id": "2s7lj", "name": "t5_2s7lj", "display_name": "rust", "subscribers": 300000,
+            "active_user_count": 1200, "over18": false, "subreddit_type": "public",
+            "community_icon": "https://styles/icon.png?width=256&amp;s=abc", "description": "Sidebar"
+        } });
+        let subreddit = Subreddit::from_data(&v["data"])?;
+        assert_eq!(subreddit.active_user_count, Some(1200));
+        assert_eq!(subreddit.subreddit_type, "public");
+        assert_eq!(subreddit.community_icon, "https://styles/icon.png?width=256&s=abc");
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_widgets() -> Result<()> {
+        let v = json!({
+            "items": {
+                "widget_id": { "kind": "id-card", "shortName": "Community", "subscribersCount": 10, "currentlyViewingCount": 2 },
+                "widget_mods": { "kind": "moderators", "mods": [{ "name": "a" }, { "name": "b" }], "totalMods": 2 },
+                "widget_text": { "kind": "textarea", "shortName": "About", "text": "Hello" },
+                "widget_cal": { "kind": "calendar", "shortName": "Events" }
+            },
+            "layout": {
+                "idCardWidget": "widget_id",
+                "moderatorWidget": "widget_mods",
+                "sidebar": { "order": ["widget_cal", "widget_text", "widget_gone"] },
+                "topbar": { "order": [] }
+            }
+        });
+
+        let widgets = Widgets::from_value(&v)?;
+        assert!(matches!(widgets.id_card.map(|w| { w.kind }), Some(WidgetKind::IdCard { subscribers_count: 10, .. })));
+        match widgets.moderators.map(|w| { w.kind }) {
+            Some(WidgetKind::Moderators { moderators, .. }) => assert_eq!(moderators, vec!["a", "b"]),
+            kind => panic!("Expected moderators, got {:?}", kind)
+        }
+        assert_eq!(widgets.sidebar.iter().map(|w| { w.short_name.as_str() }).collect::<Vec<_>>(), vec!["Events", "About"]);
+        assert!(matches!(&widgets.sidebar[0].kind, WidgetKind::Other(kind) if kind == "calendar"));
+        assert!(widgets.topbar.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_subscriptions() -> Result<()> {
+        let client = test_client();
+
+        let req = SubscribeRequest::subscribe(vec!["rust".to_string(), "programming".to_string()])?
+            .construct(&client)?;
+        assert_eq!(req.url().path(), "/api/subscribe");
+        assert_eq!(test_body(&req), "action=sub&sr_name=rust%2Cprogramming&skip_initial_defaults=true");
+
+        let req = SubscribeRequest::unsubscribe(vec!["rust".to_string()])?.construct(&client)?;
+        assert_eq!(test_body(&req), "action=unsub&sr_name=rust");
+        assert!(SubscribeRequest::subscribe(Vec::new()).is_err());
+
+        let req = SubscribeRequest::subscribe_one("rust").construct(&client)?;
+        assert_eq!(test_body(&req), "action=sub&sr_name=rust&skip_initial_defaults=true");
+        let req = SubscribeRequest::unsubscribe_one("rust").construct(&client)?;
+        assert_eq!(test_body(&req), "action=unsub&sr_name=rust");
+
+        let req = MySubredditsRequestBuilder::new(MySubredditsType::Moderator)
+            .limit(100)
+            .build()
+            .construct(&client)?;
+        assert_eq!(req.url().path(), "/subreddits/mine/moderator");
+        assert_eq!(req.url().query(), Some("limit=100"));
+
+        let v = json!({ "kind": "Listing", "data": { "after": "t5_b", "before": null, "children": [
+            { "kind": "t5", "data": { "id": "a", "name": "t5_a", "display_name": "rust" } },
+            { "kind": "t5", "data": { "id": "b", "name": "t5_b", "display_name": "programming" } }
+        ] } });
+        let listing = SubredditListing::from_value(&v)?;
+        assert_eq!(listing.after, "t5_b");
+        assert_eq!(listing.subreddits[1].display_name, "programming");
+        Ok(())
+    }
+
+    #[test]
+    fn test_subreddit_discovery() -> Result<()> {
+        let client = test_client();
+
+        let req = SubredditsRequestBuilder::new(SubredditsWhere::Popular).limit(5).build().construct(&client)?;
+        assert_eq!(req.url().path(), "/subreddits/popular");
+        assert_eq!(req.url().query(), Some("limit=5"));
+
+        let req = SubredditsRequestBuilder::new(SubredditsWhere::Search("rust".to_string(), Some(SubredditSearchSort::Activity)))
+            .build()
+            .construct(&client)?;
+        assert_eq!(req.url().path(), "/subreddits/search");
+        assert_eq!(req.url().query(), Some("q=rust&sort=activity"));
+
+        let req = SubredditAutocompleteRequestBuilder::new("ru")?
+            .limit(5)?
+            .build()
+            .construct(&client)?;
+        assert_eq!(req.url().path(), "/api/subreddit_autocomplete_v2");
+        assert_eq!(
+            req.url().query(),
+            Some("query=ru&include_over_18=false&include_profiles=false&typeahead_active=true&limit=5")
+        );
+        assert!(SubredditAutocompleteRequestBuilder::new("ru")?.limit(11).is_err());
+        assert!(SubredditAutocompleteRequestBuilder::new("").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+use serde_json::Value;
+
+use super::util::{ extract_string_with_default, Error };
+use super::account::Account;
+use super::comments::Comment;
+use super::listing::Post;
+use super::message::Message;
+use super::subreddit::Subreddit;
+
+// Any of the kinds of objects Reddit can return in a mixed listing, keyed by the `kind` prefix
+// of their fullname.
+#[derive(Clone, Debug)]
+pub enum Thing {
+    Comment(Comment),
+    Account(Account),
+    Post(Post),
+    Message(Message),
+    Subreddit(Subreddit)
+}
+
+impl Thing {
+    pub(crate) fn from_value(v: &Value) -> Result<Self, Error> {
+        match v["kind"].as_str() {
+            Some("t1") => Ok(Thing::Comment(Comment::from_data(&v["data"])?)),
+            Some("t2") => Ok(Thing::Account(Account::from_data(&v["data"])?)),
+            Some("t3") => Ok(Thing::Post(Post::from_data(&v["data"])?)),
+            Some("t4") => Ok(Thing::Message(Message::from_data(&v["data"])?)),
+            Some("t5") => Ok(Thing::Subreddit(Subreddit::from_data(&v["data"])?)),
+            kind => Err(Error::InternalError(format!("Unsupported thing kind: {:?}", kind)))
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Thing::Comment(comment) => comment.name.clone(),
+            Thing::Account(account) => account.fullname(),
+            Thing::Post(post) => post.name.clone(),
+            Thing::Message(message) => message.name.clone(),
+            Thing::Subreddit(subreddit) => subreddit.name.clone()
+        }
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct ThingListing {
+    pub after: String,
+    pub before: String,
+    pub things: Vec<Thing>
+}
+
+impl ThingListing {
+    pub(crate) fn from_value(v: &Value) -> Result<Self, Error> {
+        Ok(ThingListing {
+            after: extract_string_with_default!(v["data"]["after"], "data.after"),
+            before: extract_string_with_default!(v["data"]["before"], "data.before"),
+            things: v["data"]["children"].as_array()
+                .ok_or(Error::InternalError("Can't convert to vector".to_string()))?
+                .iter()
+                .map(Thing::from_value)
+                .collect::<Result<Vec<Thing>, Error>>()?
+        })
+    }
+
+    pub fn posts(&self) -> impl Iterator<Item = &Post> {
+        self.things.iter().filter_map(|thing| {
+            match thing {
+                Thing::Post(post) => Some(post),
+                _ => None
+            }
+        })
+    }
+
+    pub fn comments(&self) -> impl Iterator<Item = &Comment> {
+        self.things.iter().filter_map(|thing| {
+            match thing {
+                Thing::Comment(comment) => Some(comment),
+                _ => None
+            }
+        })
+    }
+}
@@ -0,0 +1,233 @@
+use reqwest::RequestBuilder as HTTPRequestBuilder;
+use async_trait::async_trait;
+
+use super::util::{ send_for_json, Request, RequestBuilder, Error };
+use super::account::Account;
+use super::listing::{ Pagination, SortTime };
+use super::thing::ThingListing;
+use crate::REDDIT_API_URL;
+use super::util::RedditClient;
+
+#[derive(Clone)]
+pub struct UserAboutRequest {
+    username: String
+}
+
+impl UserAboutRequest {
+    pub fn new(username: &str) -> Self {
+        UserAboutRequest { username: username.to_string() }
+    }
+}
+
+#[async_trait]
+impl Request<Account> for UserAboutRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        Ok(client.http_client
+            .get(format!("{}user/{}/about", REDDIT_API_URL, self.username))
+            .bearer_auth(client.access_token()?))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<Account, Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        Account::from_data(&v["data"])
+    }
+}
+
+// Which of a user's history listings to fetch. Upvoted, downvoted, hidden and saved are only
+// visible for the logged in user.
+#[derive(Clone, Debug, PartialEq)]
+pub enum UserHistory {
+    Overview,
+    Submitted,
+    Comments,
+    Upvoted,
+    Downvoted,
+    Hidden,
+    Saved,
+    Gilded
+}
+
+impl UserHistory {
+    fn as_str(&self) -> &'static str {
+        match self {
+            UserHistory::Overview => "overview",
+            UserHistory::Submitted => "submitted",
+            UserHistory::Comments => "comments",
+            UserHistory::Upvoted => "upvoted",
+            UserHistory::Downvoted => "downvoted",
+            UserHistory::Hidden => "hidden",
+            UserHistory::Saved => "saved",
+            UserHistory::Gilded => "gilded"
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum UserSort {
+    Hot,
+    New,
+    Top,
+    Controversial
+}
+
+impl UserSort {
+    fn as_str(&self) -> &'static str {
+        match self {
+            UserSort::Hot => "hot",
+            UserSort::New => "new",
+            UserSort::Top => "top",
+            UserSort::Controversial => "controversial"
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct UserListingRequest {
+    username: String,
+    history: UserHistory,
+    sort: Option<UserSort>,
+    t: Option<SortTime>,
+    category: Option<String>,
+    pagination: Pagination
+}
+
+#[async_trait]
+impl Request<ThingListing> for UserListingRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        let mut query_params: Vec<(&str, String)> = Vec::new();
+
+        self.pagination.push_query_params(&mut query_params);
+
+        if let Some(sort) = &self.sort {
+            query_params.push(("sort", sort.as_str().to_string()));
+        }
+
+        if let Some(t_sort_time) = &self.t {
+            query_params.push(("t", t_sort_time.as_str().to_string()));
+        }
+
+        if let Some(category) = &self.category {
+            query_params.push(("category", category.clone()));
+        }
+
+        Ok(client.http_client
+            .get(format!("{}user/{}/{}", REDDIT_API_URL, self.username, self.history.as_str()))
+            .bearer_auth(client.access_token()?)
+            .query(&query_params))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<ThingListing, Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        ThingListing::from_value(&v)
+    }
+}
+
+pub struct UserListingRequestBuilder {
+    req: UserListingRequest
+}
+
+impl UserListingRequestBuilder {
+    pub fn new(username: &str, history: UserHistory) -> Self {
+        UserListingRequestBuilder {
+            req: UserListingRequest {
+                username: username.to_string(),
+                history,
+                sort: None,
+                t: None,
+                category: None,
+                pagination: Pagination::default()
+            }
+        }
+    }
+
+    pub fn sort(mut self, sort: UserSort) -> Self {
+        self.req.sort = Some(sort);
+        self
+    }
+
+    pub fn t(mut self, sort_time: SortTime) -> Result<Self, Error> {
+        match self.req.sort {
+            Some(UserSort::Top) | Some(UserSort::Controversial) => {},
+            _ => { return Err(Error::UserError("Sort time needs Top or Controversial sort.".to_string())); }
+        }
+        self.req.t = Some(sort_time);
+        Ok(self)
+    }
+
+    // Only list things saved into `category`, for UserHistory::Saved.
+    pub fn category(self, category: &str) -> Result<Self, Error> {
+        if self.req.history != UserHistory::Saved {
+            return Err(Error::UserError(format!("Invalid history for category: {:?}", self.req.history)));
+        }
+        Ok(self.saved_category(category))
+    }
+
+    // Like `category`, for builders known to list UserHistory::Saved.
+    pub(crate) fn saved_category(mut self, category: &str) -> Self {
+        self.req.category = Some(category.to_string());
+        self
+    }
+
+    pub fn after(mut self, after: String) -> Self {
+        self.req.pagination.after = Some(after);
+        self
+    }
+
+    pub fn before(mut self, before: String) -> Self {
+        self.req.pagination.before = Some(before);
+        self
+    }
+
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.req.pagination.limit = Some(limit);
+        self
+    }
+
+    pub fn count(mut self, count: u32) -> Self {
+        self.req.pagination.count = Some(count);
+        self
+    }
+}
+
+impl RequestBuilder<UserListingRequest> for UserListingRequestBuilder {
+    fn build(&self) -> UserListingRequest {
+        self.req.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use super::*;
+    use crate::api::util::test_client;
+
+    #[test]
+    fn test_user_requests() -> Result<()> {
+        let client = test_client();
+
+        let req = UserAboutRequest::new("spez").construct(&client)?;
+        assert_eq!(req.url().path(), "/user/spez/about");
+
+        let req = UserListingRequestBuilder::new("spez", UserHistory::Submitted)
+            .sort(UserSort::Top)
+            .t(SortTime::Year)?
+            .after("t3_abc".to_string())
+            .limit(50)
+            .build()
+            .construct(&client)?;
+        assert_eq!(req.url().path(), "/user/spez/submitted");
+        assert_eq!(req.url().query(), Some("after=t3_abc&limit=50&sort=top&t=year"));
+
+        let req = UserListingRequestBuilder::new("spez", UserHistory::Saved)
+            .category("recipes")?
+            .limit(25)
+            .build()
+            .construct(&client)?;
+        assert_eq!(req.url().path(), "/user/spez/saved");
+        assert_eq!(req.url().query(), Some("limit=25&category=recipes"));
+
+        assert!(UserListingRequestBuilder::new("spez", UserHistory::Comments).t(SortTime::Day).is_err());
+        assert!(UserListingRequestBuilder::new("spez", UserHistory::Hidden).category("recipes").is_err());
+        Ok(())
+    }
+}
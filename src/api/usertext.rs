@@ -0,0 +1,160 @@
+use reqwest::RequestBuilder as HTTPRequestBuilder;
+use async_trait::async_trait;
+use serde_json::Value;
+
+use super::util::{ check_api_errors, send_for_json, Request, Error };
+use super::comments::Comment;
+use super::thing::Thing;
+use crate::REDDIT_API_URL;
+use super::util::RedditClient;
+
+// Reply and edit responses wrap the resulting thing in `json.data.things`.
+fn parse_resulting_thing(v: &Value) -> Result<Thing, Error> {
+    check_api_errors(v)?;
+    Thing::from_value(&v["json"]["data"]["things"][0])
+}
+
+#[derive(Clone)]
+pub struct ReplyRequest {
+    thing_id: String,
+    text: String
+}
+
+impl ReplyRequest {
+    // Replies with markdown `text` to the post (`t3_...`) or comment (`t1_...`) with fullname
+    // `thing_id`.
+    pub fn new(thing_id: &str, text: &str) -> Result<Self, Error> {
+        if !thing_id.starts_with("t1_") && !thing_id.starts_with("t3_") {
+            return Err(Error::UserError(format!("Cannot reply to: {}", thing_id)));
+        }
+        Ok(ReplyRequest {
+            thing_id: thing_id.to_string(),
+            text: text.to_string()
+        })
+    }
+}
+
+#[async_trait]
+impl Request<Comment> for ReplyRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        Ok(client.http_client
+            .post(format!("{}api/comment", REDDIT_API_URL))
+            .bearer_auth(client.access_token()?)
+            .form(&[("api_type", "json"), ("thing_id", &self.thing_id), ("text", &self.text)]))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<Comment, Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        match parse_resulting_thing(&v)? {
+            Thing::Comment(comment) => Ok(comment),
+            thing => Err(Error::InternalError(format!("Expected a comment in reply response: {}", thing.name())))
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct EditRequest {
+    thing_id: String,
+    text: String
+}
+
+impl EditRequest {
+    // Replaces the body of the user's own comment or self post with markdown `text`.
+    pub fn new(thing_id: &str, text: &str) -> Result<Self, Error> {
+        if !thing_id.starts_with("t1_") && !thing_id.starts_with("t3_") {
+            return Err(Error::UserError(format!("Not a comment or post fullname: {}", thing_id)));
+        }
+        Ok(EditRequest {
+            thing_id: thing_id.to_string(),
+            text: text.to_string()
+        })
+    }
+}
+
+#[async_trait]
+impl Request<Thing> for EditRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        Ok(client.http_client
+            .post(format!("{}api/editusertext", REDDIT_API_URL))
+            .bearer_auth(client.access_token()?)
+            .form(&[("api_type", "json"), ("thing_id", &self.thing_id), ("text", &self.text)]))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<Thing, Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        parse_resulting_thing(&v)
+    }
+}
+
+#[derive(Clone)]
+pub struct DeleteRequest {
+    id: String
+}
+
+impl DeleteRequest {
+    // Deletes the user's own comment or post.
+    pub fn new(id: &str) -> Result<Self, Error> {
+        if !id.starts_with("t1_") && !id.starts_with("t3_") {
+            return Err(Error::UserError(format!("Not a comment or post fullname: {}", id)));
+        }
+        Ok(DeleteRequest { id: id.to_string() })
+    }
+}
+
+#[async_trait]
+impl Request<()> for DeleteRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        Ok(client.http_client
+            .post(format!("{}api/del", REDDIT_API_URL))
+            .bearer_auth(client.access_token()?)
+            .form(&[("id", &self.id)]))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<(), Error> {
+        send_for_json(self.get_filled_builder(client)?).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use serde_json::json;
+    use super::*;
+    use crate::api::util::{ test_body, test_client };
+
+    #[test]
+    fn test_usertext_requests() -> Result<()> {
+        let client = test_client();
+
+        let req = ReplyRequest::new("t3_abc", "Nice post")?.construct(&client)?;
+        assert_eq!(req.url().path(), "/api/comment");
+        assert_eq!(test_body(&req), "api_type=json&thing_id=t3_abc&text=Nice+post");
+        assert!(ReplyRequest::new("t4_abc", "Hi").is_err());
+
+        let req = EditRequest::new("t1_def", "Edited")?.construct(&client)?;
+        assert_eq!(req.url().path(), "/api/editusertext");
+        assert_eq!(test_body(&req), "api_type=json&thing_id=t1_def&text=Edited");
+
+        let req = DeleteRequest::new("t1_def")?.construct(&client)?;
+        assert_eq!(req.url().path(), "/api/del");
+        assert_eq!(test_body(&req), "id=t1_def");
+        assert!(DeleteRequest::new("def").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_resulting_thing() -> Result<()> {
+        let v = json!({ "json": { "errors": [], "data": { "things": [
+            { "kind": "t1", "data": { "id": "def", "name": "t1_def", "parent_id": "t3_abc", "body": "Nice post", "replies": "" } }
+        ] } } });
+        match parse_resulting_thing(&v)? {
+            Thing::Comment(comment) => assert_eq!(comment.body, "Nice post"),
+            thing => panic!("Expected comment, got {:?}", thing)
+        }
+
+        let v = json!({ "json": { "errors": [["DELETED_COMMENT", "that comment has been deleted", "parent"]] } });
+        assert!(matches!(parse_resulting_thing(&v), Err(Error::ApiErrors(_))));
+        Ok(())
+    }
+}
@@ -1,13 +1,18 @@
 use thiserror::Error;
-use reqwest::{ Client as HTTPClient, Request as HTTPRequest, RequestBuilder as HTTPRequestBuilder };
+use reqwest::{ Client as HTTPClient, Request as HTTPRequest, RequestBuilder as HTTPRequestBuilder, StatusCode };
 use async_trait::async_trait;
 use oauth2::RefreshToken;
+use serde_json::Value;
 
 use crate::auth::oauth::OauthClient;
+use super::account::{ Account, MeRequest };
+use super::wiki::WikiConflict;
 
 pub struct RedditClient {
     pub oauth_client: OauthClient,
-    pub http_client: HTTPClient
+    pub http_client: HTTPClient,
+    // Account the client is logged in as, once fetched by `identity`.
+    pub identity: Option<Account>
 }
 
 impl RedditClient {
@@ -28,9 +33,30 @@ impl RedditClient {
             http_client: HTTPClient::builder().user_agent(user_agent).build()
                 .map_err(|e| { 
                     Error::InternalError(format!("Failed to build HTTP client from builder: {:?}.",
-                                                 e)) })?
+                                                 e)) })?,
+            identity: None
         })
     }
+
+    // Returns the account the client is logged in as, requesting it only the first time.
+    pub async fn identity(&mut self) -> Result<&Account, Error> {
+        if self.identity.is_none() {
+            self.identity = Some(MeRequest.send(self).await?);
+        }
+        self.identity.as_ref().ok_or(Error::InternalError("No identity cached.".to_string()))
+    }
+
+    // Requests the logged in account again, e.g. to update its karma or inbox count.
+    pub async fn refresh_identity(&mut self) -> Result<&Account, Error> {
+        self.identity = None;
+        self.identity().await
+    }
+
+    pub(crate) fn access_token(&self) -> Result<String, Error> {
+        Ok(self.oauth_client.access_token.as_ref().ok_or(
+                Error::InternalError("No access token found in oauth_client.".to_string())
+                )?.secret().clone())
+    }
 }
 
 #[derive(Error, Debug)]
@@ -45,16 +71,169 @@ pub enum Error {
 
     // Errors arising to invalid assertions on values or use of library functions or structures by user.
     #[error("Error: {0}")]
-    UserError(String)
+    UserError(String),
+
+    // Errors Reddit reported in the `json.errors` of an `api_type=json` response, e.g. failed
+    // validation of a submission.
+    #[error("Reddit rejected the request: {0:?}")]
+    ApiErrors(Vec<ApiError>),
+
+    // A wiki edit was based on an outdated revision of the page.
+    #[error("Wiki page was edited concurrently, its current revision is {}", .0.new_revision)]
+    WikiConflict(Box<WikiConflict>)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ApiError {
+    // Machine readable code, e.g. `SUBREDDIT_NOEXIST`.
+    pub code: String,
+    pub message: String,
+    // Name of the offending form field, if Reddit gave one.
+    pub field: Option<String>
 }
 
 #[async_trait]
 pub trait Request<S> {
     fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error>;
-    fn construct(&self, client: &RedditClient) -> Result<HTTPRequest, Error>;
+    fn construct(&self, client: &RedditClient) -> Result<HTTPRequest, Error> {
+        self.get_filled_builder(client)?
+            .build()
+            .map_err(|e| { Error::InternalError(format!("Failed to build Request from builder: {:?}", e)) })
+    }
     async fn send(&self, client: &RedditClient) -> Result<S, Error>;
 }
 
 pub trait RequestBuilder<R: Clone> {
     fn build(&self) -> R;
 }
+
+// Sends the filled builder and returns the response status and body.
+pub(crate) async fn send_for_text(builder: HTTPRequestBuilder) -> Result<(StatusCode, String), Error> {
+    let res = builder
+        .send()
+        .await
+        .map_err(|e| { Error::RequestError(format!("Error occurred while sending request: {:?}", e)) })?;
+
+    let status = res.status();
+    let text = res.text().await.map_err(|e| { Error::RequestError(e.to_string()) })?;
+    Ok((status, text))
+}
+
+// Sends the filled builder and parses the response body as JSON, turning non-success statuses into
+// RequestError.
+pub(crate) async fn send_for_json(builder: HTTPRequestBuilder) -> Result<Value, Error> {
+    let (status, text) = send_for_text(builder).await?;
+    parse_json_response(status, &text)
+}
+
+// Requests taking more items than Reddit accepts at once are sent as one request per chunk of at
+// most `max` items. Building a single request is only possible when the items fit in one chunk.
+pub(crate) fn single_chunk<T>(items: &[T], max: usize) -> Result<&[T], Error> {
+    if items.len() > max {
+        return Err(Error::UserError(format!(
+                    "Cannot fit more than {} items in a single request: {}",
+                    max,
+                    items.len())));
+    }
+    Ok(items)
+}
+
+// Sends the request built for each chunk of at most `max` items, returning the responses in order.
+pub(crate) async fn send_for_json_chunks<T, F>(items: &[T], max: usize, chunk_builder: F) -> Result<Vec<Value>, Error>
+where
+    T: Sync,
+    F: Fn(&[T]) -> Result<HTTPRequestBuilder, Error> + Send
+{
+    let mut responses: Vec<Value> = Vec::new();
+    for chunk in items.chunks(max) {
+        responses.push(send_for_json(chunk_builder(chunk)?).await?);
+    }
+    Ok(responses)
+}
+
+fn parse_json_response(status: StatusCode, text: &str) -> Result<Value, Error> {
+    if !status.is_success() {
+        return Err(Error::RequestError(format!("Reddit returned status {}: {}", status, text)));
+    }
+
+    // Some endpoints, e.g. deleting a multireddit, answer with an empty body.
+    if text.trim().is_empty() {
+        return Ok(Value::Null);
+    }
+
+    serde_json::from_str(text).map_err(|e| { Error::InternalError(e.to_string()) })
+}
+
+// Turns the `[code, message, field]` triples in `json.errors` into Error::ApiErrors.
+pub(crate) fn check_api_errors(v: &Value) -> Result<(), Error> {
+    let errors: Vec<ApiError> = v["json"]["errors"].as_array()
+        .map_or_else(Vec::new, |errors| {
+            errors.iter().map(|error| {
+                ApiError {
+                    code: error[0].as_str().unwrap_or_default().to_string(),
+                    message: error[1].as_str().unwrap_or_default().to_string(),
+                    field: error[2].as_str().map(|field| { field.to_string() })
+                }
+            }).collect()
+        });
+
+    match errors.is_empty() {
+        true => Ok(()),
+        false => Err(Error::ApiErrors(errors))
+    }
+}
+
+macro_rules! extract_string_with_default {
+    ($a: expr, $b: expr) => {
+        $a.as_str().map_or_else(|| { String::new() }, |v| { v.to_string() })
+    }
+}
+
+macro_rules! extract_string_with_error {
+    ($a: expr, $b: expr) => {
+        $a.as_str().ok_or(Error::InternalError(format!("Failed to parse to string: {}", $b)))?.to_string()
+    }
+}
+
+pub(crate) use extract_string_with_default;
+pub(crate) use extract_string_with_error;
+
+// Client with a placeholder access token, for tests that only construct requests.
+#[cfg(test)]
+pub(crate) fn test_client() -> RedditClient {
+    let mut client = RedditClient::new("client_id", "http://localhost:5555", "refresh_token", "test")
+        .expect("Failed to build test client.");
+    client.oauth_client.access_token = Some(oauth2::AccessToken::new("access_token".to_string()));
+    client
+}
+
+// Form body of a constructed request, for tests that check what would be posted.
+#[cfg(test)]
+pub(crate) fn test_body(req: &HTTPRequest) -> String {
+    String::from_utf8_lossy(req.body().and_then(|body| { body.as_bytes() }).unwrap_or_default())
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use serde_json::json;
+    use super::*;
+
+    #[test]
+    fn test_parse_json_response() -> Result<()> {
+        assert_eq!(parse_json_response(StatusCode::OK, "{\"json\": {}}")?, json!({ "json": {} }));
+        assert_eq!(parse_json_response(StatusCode::OK, "")?, Value::Null);
+        assert_eq!(parse_json_response(StatusCode::NO_CONTENT, "\n")?, Value::Null);
+        assert!(matches!(parse_json_response(StatusCode::OK, "<html>"), Err(Error::InternalError(_))));
+        assert!(matches!(parse_json_response(StatusCode::NOT_FOUND, ""), Err(Error::RequestError(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_single_chunk() -> Result<()> {
+        assert_eq!(single_chunk(&[1, 2], 2)?, &[1, 2]);
+        assert!(matches!(single_chunk(&[1, 2, 3], 2), Err(Error::UserError(_))));
+        Ok(())
+    }
+}
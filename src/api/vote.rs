@@ -0,0 +1,89 @@
+use reqwest::RequestBuilder as HTTPRequestBuilder;
+use async_trait::async_trait;
+
+use super::util::{ send_for_json, Request, Error };
+use crate::REDDIT_API_URL;
+use super::util::RedditClient;
+
+#[derive(Clone, Debug)]
+pub enum VoteDirection {
+    Up,
+    Down,
+    Clear
+}
+
+impl VoteDirection {
+    fn as_str(&self) -> &'static str {
+        match self {
+            VoteDirection::Up => "1",
+            VoteDirection::Down => "-1",
+            VoteDirection::Clear => "0"
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct VoteRequest {
+    id: String,
+    dir: VoteDirection
+}
+
+impl VoteRequest {
+    // Votes on the post (`t3_...`) or comment (`t1_...`) with fullname `id`.
+    pub fn new(id: &str, dir: VoteDirection) -> Result<Self, Error> {
+        if !id.starts_with("t1_") && !id.starts_with("t3_") {
+            return Err(Error::UserError(format!("Not a comment or post fullname: {}", id)));
+        }
+        Ok(VoteRequest {
+            id: id.to_string(),
+            dir
+        })
+    }
+
+    pub fn upvote(id: &str) -> Result<Self, Error> {
+        VoteRequest::new(id, VoteDirection::Up)
+    }
+
+    pub fn downvote(id: &str) -> Result<Self, Error> {
+        VoteRequest::new(id, VoteDirection::Down)
+    }
+
+    pub fn clear(id: &str) -> Result<Self, Error> {
+        VoteRequest::new(id, VoteDirection::Clear)
+    }
+}
+
+#[async_trait]
+impl Request<()> for VoteRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        Ok(client.http_client
+            .post(format!("{}api/vote", REDDIT_API_URL))
+            .bearer_auth(client.access_token()?)
+            .form(&[("id", self.id.as_str()), ("dir", self.dir.as_str())]))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<(), Error> {
+        send_for_json(self.get_filled_builder(client)?).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use super::*;
+    use crate::api::util::{ test_body, test_client };
+
+    #[test]
+    fn test_vote_request() -> Result<()> {
+        let client = test_client();
+
+        let req = VoteRequest::upvote("t3_abc")?.construct(&client)?;
+        assert_eq!(req.url().path(), "/api/vote");
+        assert_eq!(test_body(&req), "id=t3_abc&dir=1");
+        assert_eq!(test_body(&VoteRequest::downvote("t1_def")?.construct(&client)?), "id=t1_def&dir=-1");
+        assert_eq!(test_body(&VoteRequest::clear("t1_def")?.construct(&client)?), "id=t1_def&dir=0");
+        assert!(VoteRequest::upvote("t5_abc").is_err());
+        Ok(())
+    }
+}
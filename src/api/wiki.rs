@@ -0,0 +1,642 @@
+use reqwest::{ RequestBuilder as HTTPRequestBuilder, StatusCode };
+use async_trait::async_trait;
+use serde_json::Value;
+
+use super::util::{
+    extract_string_with_default,
+    extract_string_with_error,
+    send_for_json,
+    send_for_text,
+    Request,
+    RequestBuilder,
+    Error
+};
+use super::listing::Pagination;
+use crate::REDDIT_API_URL;
+use super::util::RedditClient;
+
+#[derive(Clone, Default, Debug)]
+pub struct WikiPage {
+    pub content_md: String,
+    pub content_html: String,
+    // Id of the revision the content is from. Empty for pages that were never edited.
+    pub revision_id: String,
+    // Name of the user who made the revision.
+    pub revision_by: String,
+    pub revision_date: f64,
+    // Revision reason given by the editor.
+    pub reason: String,
+    // Whether the current user may edit the page.
+    pub may_revise: bool
+}
+
+impl WikiPage {
+    // Parses the `data` object of a `wikipage`.
+    pub(crate) fn from_data(data: &Value) -> Result<Self, Error> {
+        Ok(WikiPage {
+            content_md: extract_string_with_error!(data["content_md"], "data.content_md"),
+            content_html: extract_string_with_default!(data["content_html"], "data.content_html"),
+            revision_id: extract_string_with_default!(data["revision_id"], "data.revision_id"),
+            revision_by: extract_string_with_default!(data["revision_by"]["data"]["name"], "data.revision_by.data.name"),
+            revision_date: data["revision_date"].as_f64().unwrap_or_default(),
+            reason: extract_string_with_default!(data["reason"], "data.reason"),
+            may_revise: data["may_revise"].as_bool().unwrap_or_default()
+        })
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct WikiRevision {
+    pub id: String,
+    pub page: String,
+    pub author: String,
+    pub reason: String,
+    // Milliseconds since the epoch.
+    pub timestamp: u64,
+    // Whether moderators hid the revision from the page history.
+    pub revision_hidden: bool
+}
+
+impl WikiRevision {
+    pub(crate) fn from_data(data: &Value) -> Result<Self, Error> {
+        Ok(WikiRevision {
+            id: extract_string_with_error!(data["id"], "data.id"),
+            page: extract_string_with_default!(data["page"], "data.page"),
+            author: extract_string_with_default!(data["author"]["data"]["name"], "data.author.data.name"),
+            reason: extract_string_with_default!(data["reason"], "data.reason"),
+            timestamp: data["timestamp"].as_u64().unwrap_or_default(),
+            revision_hidden: data["revision_hidden"].as_bool().unwrap_or_default()
+        })
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct WikiRevisionListing {
+    pub after: String,
+    pub before: String,
+    pub revisions: Vec<WikiRevision>
+}
+
+impl WikiRevisionListing {
+    pub(crate) fn from_value(v: &Value) -> Result<Self, Error> {
+        Ok(WikiRevisionListing {
+            after: extract_string_with_default!(v["data"]["after"], "data.after"),
+            before: extract_string_with_default!(v["data"]["before"], "data.before"),
+            revisions: v["data"]["children"].as_array()
+                .ok_or(Error::InternalError("Can't convert to vector".to_string()))?
+                .iter()
+                .map(|v_revision| -> Result<WikiRevision, Error> { WikiRevision::from_data(v_revision) })
+                .collect::<Result<Vec<WikiRevision>, Error>>()?
+        })
+    }
+}
+
+// Names of the wiki pages of a subreddit, e.g. `index` or `config/sidebar`.
+#[derive(Clone)]
+pub struct WikiPagesRequest {
+    subreddit: String
+}
+
+impl WikiPagesRequest {
+    pub fn new(subreddit: &str) -> Self {
+        WikiPagesRequest { subreddit: subreddit.to_string() }
+    }
+}
+
+#[async_trait]
+impl Request<Vec<String>> for WikiPagesRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        Ok(client.http_client
+            .get(format!("{}r/{}/wiki/pages", REDDIT_API_URL, self.subreddit))
+            .bearer_auth(client.access_token()?))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<Vec<String>, Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        Ok(v["data"].as_array()
+            .ok_or(Error::InternalError("Can't convert to vector".to_string()))?
+            .iter()
+            .filter_map(|page| { page.as_str().map(|page| { page.to_string() }) })
+            .collect())
+    }
+}
+
+#[derive(Clone)]
+pub struct WikiPageRequest {
+    subreddit: String,
+    page: String,
+    revision: Option<String>
+}
+
+#[async_trait]
+impl Request<WikiPage> for WikiPageRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        let mut query_params: Vec<(&str, String)> = Vec::new();
+
+        if let Some(revision) = &self.revision {
+            query_params.push(("v", revision.clone()));
+        }
+
+        Ok(client.http_client
+            .get(format!("{}r/{}/wiki/{}", REDDIT_API_URL, self.subreddit, self.page))
+            .bearer_auth(client.access_token()?)
+            .query(&query_params))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<WikiPage, Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        WikiPage::from_data(&v["data"])
+    }
+}
+
+pub struct WikiPageRequestBuilder {
+    req: WikiPageRequest
+}
+
+impl WikiPageRequestBuilder {
+    pub fn new(subreddit: &str, page: &str) -> Self {
+        WikiPageRequestBuilder {
+            req: WikiPageRequest {
+                subreddit: subreddit.to_string(),
+                page: page.to_string(),
+                revision: None
+            }
+        }
+    }
+
+    // Fetch the page as of the revision with id `revision` instead of the current one.
+    pub fn revision(mut self, revision: &str) -> Self {
+        self.req.revision = Some(revision.to_string());
+        self
+    }
+}
+
+impl RequestBuilder<WikiPageRequest> for WikiPageRequestBuilder {
+    fn build(&self) -> WikiPageRequest {
+        self.req.clone()
+    }
+}
+
+#[derive(Clone)]
+pub struct WikiRevisionsRequest {
+    subreddit: String,
+    page: String,
+    pagination: Pagination
+}
+
+#[async_trait]
+impl Request<WikiRevisionListing> for WikiRevisionsRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        let mut query_params: Vec<(&str, String)> = Vec::new();
+
+        self.pagination.push_query_params(&mut query_params);
+
+        Ok(client.http_client
+            .get(format!("{}r/{}/wiki/revisions/{}", REDDIT_API_URL, self.subreddit, self.page))
+            .bearer_auth(client.access_token()?)
+            .query(&query_params))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<WikiRevisionListing, Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        WikiRevisionListing::from_value(&v)
+    }
+}
+
+pub struct WikiRevisionsRequestBuilder {
+    req: WikiRevisionsRequest
+}
+
+impl WikiRevisionsRequestBuilder {
+    // Revision history of `page`, newest first.
+    pub fn new(subreddit: &str, page: &str) -> Self {
+        WikiRevisionsRequestBuilder {
+            req: WikiRevisionsRequest {
+                subreddit: subreddit.to_string(),
+                page: page.to_string(),
+                pagination: Pagination::default()
+            }
+        }
+    }
+
+    pub fn after(mut self, after: String) -> Self {
+        self.req.pagination.after = Some(after);
+        self
+    }
+
+    pub fn before(mut self, before: String) -> Self {
+        self.req.pagination.before = Some(before);
+        self
+    }
+
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.req.pagination.limit = Some(limit);
+        self
+    }
+
+    pub fn count(mut self, count: u32) -> Self {
+        self.req.pagination.count = Some(count);
+        self
+    }
+}
+
+impl RequestBuilder<WikiRevisionsRequest> for WikiRevisionsRequestBuilder {
+    fn build(&self) -> WikiRevisionsRequest {
+        self.req.clone()
+    }
+}
+
+// Current state of a wiki page whose edit conflicted with another edit, returned in
+// Error::WikiConflict.
+#[derive(Clone, Default, Debug)]
+pub struct WikiConflict {
+    // Id of the page's current revision. Pass it as the previous revision to retry the edit.
+    pub new_revision: String,
+    // Markdown content of the current revision.
+    pub new_content: String,
+    // HTML diff between the edit and the current revision.
+    pub diff_content: String
+}
+
+impl WikiConflict {
+    pub(crate) fn from_value(v: &Value) -> Self {
+        WikiConflict {
+            new_revision: extract_string_with_default!(v["newrevision"], "newrevision"),
+            new_content: extract_string_with_default!(v["newcontent"], "newcontent"),
+            diff_content: extract_string_with_default!(v["diffcontent"], "diffcontent")
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct WikiEditRequest {
+    subreddit: String,
+    page: String,
+    content: String,
+    reason: Option<String>,
+    previous: Option<String>
+}
+
+#[async_trait]
+impl Request<()> for WikiEditRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        let mut form_params: Vec<(&str, String)> = vec![
+            ("page", self.page.clone()),
+            ("content", self.content.clone())
+        ];
+
+        if let Some(reason) = &self.reason {
+            form_params.push(("reason", reason.clone()));
+        }
+
+        if let Some(previous) = &self.previous {
+            form_params.push(("previous", previous.clone()));
+        }
+
+        Ok(client.http_client
+            .post(format!("{}r/{}/api/wiki/edit", REDDIT_API_URL, self.subreddit))
+            .bearer_auth(client.access_token()?)
+            .form(&form_params))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<(), Error> {
+        let (status, text) = send_for_text(self.get_filled_builder(client)?).await?;
+        parse_edit_response(status, &text)
+    }
+}
+
+fn parse_edit_response(status: StatusCode, text: &str) -> Result<(), Error> {
+    match status {
+        StatusCode::CONFLICT => {
+            let v: Value = serde_json::from_str(text).map_err(|e| { Error::InternalError(e.to_string()) })?;
+            Err(Error::WikiConflict(Box::new(WikiConflict::from_value(&v))))
+        },
+        status if status.is_success() => Ok(()),
+        status => Err(Error::RequestError(format!("Reddit returned status {}: {}", status, text)))
+    }
+}
+
+pub struct WikiEditRequestBuilder {
+    req: WikiEditRequest
+}
+
+impl WikiEditRequestBuilder {
+    // Replaces the content of `page` with markdown `content`, creating the page if needed.
+    pub fn new(subreddit: &str, page: &str, content: &str) -> Self {
+        WikiEditRequestBuilder {
+            req: WikiEditRequest {
+                subreddit: subreddit.to_string(),
+                page: page.to_string(),
+                content: content.to_string(),
+                reason: None,
+                previous: None
+            }
+        }
+    }
+
+    // Revision reason shown in the page history.
+    pub fn reason(mut self, reason: &str) -> Self {
+        self.req.reason = Some(reason.to_string());
+        self
+    }
+
+    // Id of the revision the edit is based on. If the page has been revised since, the edit fails
+    // with Error::WikiConflict instead of overwriting the newer revision.
+    pub fn previous(mut self, previous: &str) -> Self {
+        self.req.previous = Some(previous.to_string());
+        self
+    }
+}
+
+impl RequestBuilder<WikiEditRequest> for WikiEditRequestBuilder {
+    fn build(&self) -> WikiEditRequest {
+        self.req.clone()
+    }
+}
+
+// Makes the revision with id `revision` the current content of `page`. Requires moderator
+// permissions.
+#[derive(Clone)]
+pub struct WikiRevertRequest {
+    subreddit: String,
+    page: String,
+    revision: String
+}
+
+impl WikiRevertRequest {
+    pub fn new(subreddit: &str, page: &str, revision: &str) -> Self {
+        WikiRevertRequest {
+            subreddit: subreddit.to_string(),
+            page: page.to_string(),
+            revision: revision.to_string()
+        }
+    }
+}
+
+#[async_trait]
+impl Request<()> for WikiRevertRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        Ok(client.http_client
+            .post(format!("{}r/{}/api/wiki/revert", REDDIT_API_URL, self.subreddit))
+            .bearer_auth(client.access_token()?)
+            .form(&[("page", &self.page), ("revision", &self.revision)]))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<(), Error> {
+        send_for_json(self.get_filled_builder(client)?).await?;
+        Ok(())
+    }
+}
+
+// Who may edit a wiki page.
+#[derive(Clone, Debug, PartialEq)]
+pub enum WikiPermLevel {
+    // The subreddit's wiki settings apply.
+    SubredditDefault,
+    // Only approved editors and moderators.
+    ApprovedEditors,
+    ModeratorsOnly
+}
+
+impl WikiPermLevel {
+    fn as_u64(&self) -> u64 {
+        match self {
+            WikiPermLevel::SubredditDefault => 0,
+            WikiPermLevel::ApprovedEditors => 1,
+            WikiPermLevel::ModeratorsOnly => 2
+        }
+    }
+
+    fn from_u64(permlevel: u64) -> Result<Self, Error> {
+        match permlevel {
+            0 => Ok(WikiPermLevel::SubredditDefault),
+            1 => Ok(WikiPermLevel::ApprovedEditors),
+            2 => Ok(WikiPermLevel::ModeratorsOnly),
+            _ => Err(Error::InternalError(format!("Unknown wiki permlevel: {}", permlevel)))
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct WikiPageSettings {
+    pub permlevel: WikiPermLevel,
+    // Whether the page is shown in the page list.
+    pub listed: bool,
+    // Names of the users approved to edit the page.
+    pub editors: Vec<String>
+}
+
+impl WikiPageSettings {
+    // Parses the `data` object of a `wikipagesettings`.
+    pub(crate) fn from_data(data: &Value) -> Result<Self, Error> {
+        Ok(WikiPageSettings {
+            permlevel: WikiPermLevel::from_u64(data["permlevel"].as_u64()
+                .ok_or(Error::InternalError("Failed to parse to u64: data.permlevel".to_string()))?)?,
+            listed: data["listed"].as_bool().unwrap_or_default(),
+            editors: data["editors"].as_array()
+                .map_or_else(Vec::new, |editors| {
+                    editors.iter()
+                        .filter_map(|editor| { editor["data"]["name"].as_str().map(|name| { name.to_string() }) })
+                        .collect()
+                })
+        })
+    }
+}
+
+// Settings of a wiki page. Requires moderator permissions.
+#[derive(Clone)]
+pub struct WikiSettingsRequest {
+    subreddit: String,
+    page: String,
+    update: Option<(WikiPermLevel, bool)>
+}
+
+impl WikiSettingsRequest {
+    pub fn new(subreddit: &str, page: &str) -> Self {
+        WikiSettingsRequest {
+            subreddit: subreddit.to_string(),
+            page: page.to_string(),
+            update: None
+        }
+    }
+
+    // Changes who may edit the page and whether it is listed, returning the new settings.
+    pub fn update(subreddit: &str, page: &str, permlevel: WikiPermLevel, listed: bool) -> Self {
+        let mut req = WikiSettingsRequest::new(subreddit, page);
+        req.update = Some((permlevel, listed));
+        req
+    }
+}
+
+#[async_trait]
+impl Request<WikiPageSettings> for WikiSettingsRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        let url = format!("{}r/{}/wiki/settings/{}", REDDIT_API_URL, self.subreddit, self.page);
+
+        Ok(match &self.update {
+            Some((permlevel, listed)) => client.http_client
+                .post(url)
+                .form(&[
+                      ("page", self.page.clone()),
+                      ("permlevel", permlevel.as_u64().to_string()),
+                      ("listed", listed.to_string())
+                ]),
+            None => client.http_client.get(url)
+        }.bearer_auth(client.access_token()?))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<WikiPageSettings, Error> {
+        let v = send_for_json(self.get_filled_builder(client)?).await?;
+        WikiPageSettings::from_data(&v["data"])
+    }
+}
+
+// Approves or unapproves a user to edit a wiki page. Requires moderator permissions.
+#[derive(Clone)]
+pub struct WikiEditorRequest {
+    subreddit: String,
+    page: String,
+    username: String,
+    remove: bool
+}
+
+impl WikiEditorRequest {
+    pub fn add(subreddit: &str, page: &str, username: &str) -> Self {
+        WikiEditorRequest {
+            subreddit: subreddit.to_string(),
+            page: page.to_string(),
+            username: username.to_string(),
+            remove: false
+        }
+    }
+
+    pub fn remove(subreddit: &str, page: &str, username: &str) -> Self {
+        let mut req = WikiEditorRequest::add(subreddit, page, username);
+        req.remove = true;
+        req
+    }
+}
+
+#[async_trait]
+impl Request<()> for WikiEditorRequest {
+    fn get_filled_builder(&self, client: &RedditClient) -> Result<HTTPRequestBuilder, Error> {
+        let act = match self.remove {
+            true => "del",
+            false => "add"
+        };
+
+        Ok(client.http_client
+            .post(format!("{}r/{}/api/wiki/alloweditor/{}", REDDIT_API_URL, self.subreddit, act))
+            .bearer_auth(client.access_token()?)
+            .form(&[("page", &self.page), ("username", &self.username)]))
+    }
+
+    async fn send(&self, client: &RedditClient) -> Result<(), Error> {
+        send_for_json(self.get_filled_builder(client)?).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use serde_json::json;
+    use super::*;
+    use crate::api::util::{ test_body, test_client };
+
+    #[test]
+    fn test_wiki_requests() -> Result<()> {
+        let client = test_client();
+
+        assert_eq!(WikiPagesRequest::new("rust").construct(&client)?.url().path(), "/r/rust/wiki/pages");
+
+        let req = WikiPageRequestBuilder::new("rust", "config/sidebar")
+            .revision("3c8d1e2a")
+            .build()
+            .construct(&client)?;
+        assert_eq!(req.url().path(), "/r/rust/wiki/config/sidebar");
+        assert_eq!(req.url().query(), Some("v=3c8d1e2a"));
+
+        let req = WikiRevisionsRequestBuilder::new("rust", "index").limit(25).build().construct(&client)?;
+        assert_eq!(req.url().path(), "/r/rust/wiki/revisions/index");
+        assert_eq!(req.url().query(), Some("limit=25"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_wiki() -> Result<()> {
+        let v = json!({ "kind": "wikipage", "data": {
+            "content_md": "# Welcome", "content_html": "<h1>Welcome</h1>", "may_revise": false,
+            "revision_id": "3c8d1e2a", "revision_date": 1666000000.0, "reason": null,
+            "revision_by": { "kind": "t2", "data": { "name": "bob" } }
+        } });
+        let page = WikiPage::from_data(&v["data"])?;
+        assert_eq!(page.revision_by, "bob");
+        assert_eq!(page.revision_id, "3c8d1e2a");
+        assert!(page.reason.is_empty());
+
+        let v = json!({ "kind": "Listing", "data": { "after": "WikiRevision_3c8d1e2a", "children": [
+            { "id": "3c8d1e2a", "page": "index", "timestamp": 1666000000000u64, "reason": "typo",
+              "revision_hidden": false, "author": { "kind": "t2", "data": { "name": "bob" } } }
+        ] } });
+        let listing = WikiRevisionListing::from_value(&v)?;
+        assert_eq!(listing.after, "WikiRevision_3c8d1e2a");
+        assert_eq!(listing.revisions[0].author, "bob");
+        assert_eq!(listing.revisions[0].timestamp, 1666000000000);
+        Ok(())
+    }
+
+    #[test]
+    fn test_wiki_edit_requests() -> Result<()> {
+        let client = test_client();
+
+        let req = WikiEditRequestBuilder::new("rust", "index", "# Welcome")
+            .reason("typo")
+            .previous("3c8d1e2a")
+            .build()
+            .construct(&client)?;
+        assert_eq!(req.url().path(), "/r/rust/api/wiki/edit");
+        assert_eq!(test_body(&req), "page=index&content=%23+Welcome&reason=typo&previous=3c8d1e2a");
+
+        let req = WikiRevertRequest::new("rust", "index", "3c8d1e2a").construct(&client)?;
+        assert_eq!(req.url().path(), "/r/rust/api/wiki/revert");
+        assert_eq!(test_body(&req), "page=index&revision=3c8d1e2a");
+
+        let req = WikiSettingsRequest::update("rust", "index", WikiPermLevel::ModeratorsOnly, false).construct(&client)?;
+        assert_eq!(req.method(), "POST");
+        assert_eq!(req.url().path(), "/r/rust/wiki/settings/index");
+        assert_eq!(test_body(&req), "page=index&permlevel=2&listed=false");
+
+        let req = WikiEditorRequest::remove("rust", "index", "bob").construct(&client)?;
+        assert_eq!(req.url().path(), "/r/rust/api/wiki/alloweditor/del");
+        assert_eq!(test_body(&req), "page=index&username=bob");
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_wiki_edit_response() -> Result<()> {
+        assert!(parse_edit_response(StatusCode::OK, "{}").is_ok());
+
+        let text = json!({
+            "message": "Conflict", "reason": "EDIT_CONFLICT", "newrevision": "9f0e7b6c",
+            "newcontent": "# Welcome!", "diffcontent": "<del>#Welcome</del>"
+        }).to_string();
+        match parse_edit_response(StatusCode::CONFLICT, &text) {
+            Err(Error::WikiConflict(conflict)) => {
+                assert_eq!(conflict.new_revision, "9f0e7b6c");
+                assert_eq!(conflict.new_content, "# Welcome!");
+            },
+            result => panic!("Expected a wiki conflict, got {:?}", result)
+        }
+
+        assert!(matches!(parse_edit_response(StatusCode::FORBIDDEN, "{}"), Err(Error::RequestError(_))));
+
+        let v = json!({ "kind": "wikipagesettings", "data": {
+            "permlevel": 1, "listed": true, "editors": [{ "kind": "t2", "data": { "name": "bob" } }]
+        } });
+        let settings = WikiPageSettings::from_data(&v["data"])?;
+        assert_eq!(settings.permlevel, WikiPermLevel::ApprovedEditors);
+        assert_eq!(settings.editors, vec!["bob"]);
+        Ok(())
+    }
+}